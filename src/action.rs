@@ -0,0 +1,431 @@
+//! Actions are the only way the game changes.
+//!
+//! Input handlers turn what they receive into an `Action` and pass it to
+//! `apply`, which checks it against the rules, changes `GameState`, and records
+//! it. Keeping one writer means the game never needs to share or lock its grid.
+//!
+//! The recorded actions, together with the seed, are the whole game. Exported
+//! games (`notation`) are just that list, undo (`history`) replays it minus
+//! the last move, and saves carry it along so undo still works after a resume.
+//! Anything else that needs to follow a game move by move should read the list
+//! rather than watch the state.
+
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    style::{Color, Style, Styled},
+    text::Span,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Error, EventResponse, GamePhase, GameState,
+    analysis::StyleScore,
+    dictionary::{BLANK, blank_as, is_played_blank},
+    grid::{Coordinate, Grid, Word},
+    rules::Mutator,
+    solver,
+};
+
+/// Something a player does that changes the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Puts a tile from the hand down on the board.
+    Place(char, Coordinate),
+    /// Picks a tile up off the board and back into the hand.
+    PickUp(Coordinate),
+    /// Picks up a tile and every one after it in a direction, up to the first gap.
+    PickUpRun(Coordinate, Coordinate),
+    /// Moves every tile between two corners by an offset, all together.
+    Move(Coordinate, Coordinate, Coordinate),
+    /// Trades a tile from the hand for three from the pile.
+    Dump(char),
+    /// Checks the board, then pulls a new tile or finishes the game.
+    Peel,
+    /// Ends a timed game, scoring the board as it stands.
+    Buzzer,
+    /// Asks for a word to play, out of the game's hint budget.
+    Hint,
+    /// The bot peels, and everyone draws.
+    BotPeel,
+}
+
+/// How many hints a game gets, and what each costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HintBudget {
+    pub per_game: usize,
+    /// The percentage of the score each hint takes off.
+    pub cost_percent: i64,
+}
+
+impl Default for HintBudget {
+    fn default() -> Self {
+        Self {
+            per_game: 3,
+            cost_percent: 10,
+        }
+    }
+}
+
+/// Applies an action to the game, recording it if it changed anything.
+pub fn apply(state: &mut GameState, action: Action) -> Result<EventResponse, Error> {
+    let recorded: usize = state.actions.len();
+    let response: Result<EventResponse, Error> = perform(state, action);
+    if state.actions.len() > recorded {
+        state.history.clear();
+        let elapsed: Duration = state.elapsed();
+        state.action_times.resize(state.actions.len(), elapsed);
+    }
+    response
+}
+
+/// Checks an action and makes it, for `apply`.
+fn perform(state: &mut GameState, action: Action) -> Result<EventResponse, Error> {
+    if !matches!(state.phase, GamePhase::Playing | GamePhase::Reviewing) {
+        return Ok(EventResponse::Pass);
+    }
+
+    match action {
+        Action::Place(letter, coordinate) => {
+            if !state.distribution.contains_letter(letter) {
+                return Ok(EventResponse::Pass);
+            }
+            // Play a blank for the letter if it's the only way to play it.
+            let (tile, used): (char, char) = if state.hand.contains(letter) {
+                (letter, letter)
+            } else if state.hand.contains(BLANK)
+                && let Some(tile) = blank_as(letter)
+            {
+                (tile, BLANK)
+            } else {
+                return Ok(EventResponse::Pass);
+            };
+
+            // Check if a tile was actually put down before removing it from our hand.
+            if state.camera.put(coordinate, tile) {
+                state.hand.take(used);
+                state.camera.check_health(&state.dictionary, &state.rules);
+                state.actions.push(action);
+                return Ok(EventResponse::Announce(
+                    if used == BLANK {
+                        format!("Placed a blank as '{letter}' at {coordinate}.")
+                    } else {
+                        format!("Placed '{letter}' at {coordinate}.")
+                    }
+                    .into(),
+                ));
+            }
+        }
+        Action::PickUp(coordinate) => {
+            if let Some(tile) = state.camera.pick_up(coordinate) {
+                // Blanks go back to being blank.
+                let tile: char = if is_played_blank(tile) { BLANK } else { tile };
+                state.hand.add(tile);
+                state.camera.check_health(&state.dictionary, &state.rules);
+                state.actions.push(action);
+                return Ok(EventResponse::Announce(
+                    format!("Picked up '{tile}' from {coordinate}.").into(),
+                ));
+            }
+        }
+        Action::PickUpRun(start, direction) => {
+            let mut picked: usize = 0;
+            let mut coordinate: Coordinate = start;
+            while let Some(tile) = state.camera.pick_up(coordinate) {
+                state
+                    .hand
+                    .add(if is_played_blank(tile) { BLANK } else { tile });
+                picked += 1;
+                let (next, overflowed) = coordinate.overflowing_add(direction);
+                if overflowed {
+                    break;
+                }
+                coordinate = next;
+            }
+            if picked > 0 {
+                state.camera.check_health(&state.dictionary, &state.rules);
+                state.actions.push(action);
+                return Ok(EventResponse::Announce(
+                    format!("Picked up {picked} tiles from {start}.").into(),
+                ));
+            }
+        }
+        Action::Move(min, max, offset) => return move_tiles(state, (min, max), offset),
+        Action::Dump(letter) => return dump(state, letter),
+        Action::Hint => return Ok(hint(state)),
+        Action::Peel => return peel(state),
+        Action::Buzzer => return Ok(buzzer(state)),
+        Action::BotPeel => return bot_peel(state),
+    }
+
+    Ok(EventResponse::Pass)
+}
+
+/// Moves every tile in a region by an offset, as long as none of them land on another.
+fn move_tiles(
+    state: &mut GameState,
+    (min, max): (Coordinate, Coordinate),
+    offset: Coordinate,
+) -> Result<EventResponse, Error> {
+    if offset == Coordinate::default() {
+        return Ok(EventResponse::Pass);
+    }
+    let moved: usize = state.camera.move_tiles((min, max), offset)?;
+    if moved == 0 {
+        return Ok(EventResponse::Pass);
+    }
+    state.camera.check_health(&state.dictionary, &state.rules);
+    state.actions.push(Action::Move(min, max, offset));
+    Ok(EventResponse::Announce(
+        format!("Moved {moved} tiles by {offset}.").into(),
+    ))
+}
+
+/// Checks the board the way a peel does, without changing anything.
+pub fn validate_board(state: &GameState) -> Result<(), Error> {
+    let words: &[Word] = &state.camera.stats.words;
+    state
+        .camera
+        .grid
+        .validate_connectivity()
+        .and_then(|()| Grid::validate_words(words, &state.dictionary))
+        .and_then(|()| state.rules.validate_words(words))
+        .map_err(Error::from)
+}
+
+/// Picks out what a failed check blamed: the tiles on the board, and the word to
+/// offer for the house dictionary.
+pub fn blame(state: &mut GameState, exception: &Error) {
+    state.camera.flagged = exception.cells().iter().copied().collect();
+    state.rejected_word = match exception {
+        Error::Core(guavagrams::Error::InvalidWord(word, _)) => Some(word.clone()),
+        _ => None,
+    };
+}
+
+/// Trades a tile from the hand for three from the pile, at the cost of 5% of the score.
+///
+/// Nothing changes, and nothing is deducted, unless the trade goes through.
+fn dump(state: &mut GameState, letter: char) -> Result<EventResponse, Error> {
+    if !state.distribution.contains_tile(letter) || !state.hand.contains(letter) {
+        return Err(Error::NotInHand(letter));
+    }
+
+    if state.endless {
+        // The traded tile goes back into a pile that's never counted.
+        state.hand.take(letter);
+        state.draw_tiles(3)?;
+    } else {
+        state.pile.dump(&mut state.hand, letter, &mut state.rng)?;
+    }
+    state.change_score(-state.score / 20);
+    state.actions.push(Action::Dump(letter));
+    if let Some(response) = end_if_endgame(state) {
+        return Ok(response);
+    }
+
+    Ok(EventResponse::ChangeStatus(
+        "Deducted 5% of points for trading in tiles.".set_style(Style::new().fg(Color::Red)),
+    ))
+}
+
+/// Checks the board, then pulls a new tile or finishes the game.
+fn peel(state: &mut GameState) -> Result<EventResponse, Error> {
+    if !state.hand.is_empty() {
+        return Err(Error::HandHasTiles);
+    }
+    state.actions.push(Action::Peel);
+    // Endless games never get to a last peel.
+    let last_peel: bool = state.is_endgame();
+
+    if let Err(exception) = validate_board(state) {
+        blame(state, &exception);
+        // There's nobody else to keep playing once a rotten banana knocks you out,
+        // so the game just ends there.
+        if last_peel && state.rules.contains(Mutator::RottenBanana) {
+            state.change_score(-state.score);
+            finish(state);
+            return Ok(EventResponse::ChangeStatus(
+                format!("Rotten banana! {exception}").set_style(Style::new().fg(Color::Red)),
+            ));
+        }
+        state.change_score(-state.score / 20);
+        return Err(exception);
+    }
+
+    let delta: i64 = Grid::score_grid(&state.camera.stats.words, &state.scoring, &state.rules);
+    state.change_score(delta);
+
+    if let Some(target) = state.target_score
+        && state.score >= target
+    {
+        finish(state);
+        return Ok(EventResponse::ChangeStatus(
+            format!("Target reached! {} points.", state.score)
+                .set_style(Style::new().fg(Color::Green)),
+        ));
+    }
+    if last_peel {
+        finish(state);
+        return Ok(EventResponse::ChangeStatus(
+            "Guavagrams!".set_style(Style::new().fg(Color::Green)),
+        ));
+    }
+    state.hand.forget_new_tiles();
+    state.draw_tiles(1)?;
+    state.draw_for_bot()?;
+    if let Some(response) = end_if_endgame(state) {
+        return Ok(response);
+    }
+    Ok(EventResponse::ChangeStatus(
+        "Peel!".set_style(Style::new().fg(Color::Green)),
+    ))
+}
+
+/// The bot peels: everyone draws a tile, unless there aren't enough to go round,
+/// in which case the bot's gone out first and the game's over.
+fn bot_peel(state: &mut GameState) -> Result<EventResponse, Error> {
+    state.actions.push(Action::BotPeel);
+    if state.is_endgame() {
+        finish(state);
+        return Ok(EventResponse::ChangeStatus(
+            "Bananas! The bot went out first.".set_style(Style::new().fg(Color::Red)),
+        ));
+    }
+
+    state.draw_tiles(1)?;
+    state.draw_for_bot()?;
+    let elapsed: Duration = state.elapsed();
+    if let Some(bot) = &mut state.bot {
+        bot.peels += 1;
+        bot.last_peel = Some(elapsed);
+    }
+    if let Some(response) = end_if_endgame(state) {
+        return Ok(response);
+    }
+    Ok(EventResponse::ChangeStatus(
+        "The bot peeled! You draw a tile.".set_style(Style::new().fg(Color::Yellow)),
+    ))
+}
+
+/// Suggests a word to play, and takes the hint's cost off the score, if there are hints left.
+fn hint(state: &mut GameState) -> EventResponse {
+    let used: usize = state.hints_used();
+    if used >= state.hints.per_game {
+        return EventResponse::ChangeStatus(
+            format!("No hints left. This game gets {}.", state.hints.per_game)
+                .set_style(Style::new().fg(Color::Yellow)),
+        );
+    }
+    let Some(hint) = solver::suggest(
+        state.hand.tiles(),
+        &state.camera.grid,
+        &state.dictionary,
+        &state.scoring,
+        &state.rules,
+        state.camera.cursor,
+    ) else {
+        return EventResponse::ChangeStatus(
+            "No hints. Nothing in your hand fits anywhere simple."
+                .set_style(Style::new().fg(Color::Yellow)),
+        );
+    };
+    let cost: i64 = state.score * state.hints.cost_percent / 100;
+    state.actions.push(Action::Hint);
+    state.change_score(-cost);
+    state.camera.ghost = hint.placements.into_iter().collect();
+    EventResponse::ChangeStatus(
+        format!(
+            "Try \"{}\" from {}. That hint cost {cost} points, and {} are left.",
+            hint.word.text,
+            hint.word.start,
+            state.hints.per_game - used - 1
+        )
+        .set_style(Style::new().fg(Color::Cyan)),
+    )
+}
+
+/// Scores the board as it stands when time runs out, then ends the game.
+fn buzzer(state: &mut GameState) -> EventResponse {
+    state.actions.push(Action::Buzzer);
+
+    let status: Span<'static> = match validate_board(state) {
+        Ok(()) => {
+            let delta: i64 =
+                Grid::score_grid(&state.camera.stats.words, &state.scoring, &state.rules);
+            state.change_score(delta);
+            format!("Time's up! The board was worth {delta} points.")
+                .set_style(Style::new().fg(Color::Green))
+        }
+        Err(exception) => {
+            blame(state, &exception);
+            format!("Time's up! {exception} The board doesn't score.")
+                .set_style(Style::new().fg(Color::Red))
+        }
+    };
+    finish(state);
+    EventResponse::ChangeStatus(status)
+}
+
+/// Stops the game clock and looks over the final board.
+fn finish(state: &mut GameState) {
+    state.phase = GamePhase::Finished;
+    state.game_end = Some(Instant::now());
+    state.style = StyleScore::of(&state.camera.grid, &state.camera.stats.words);
+}
+
+/// Under `Mutator::DumpRemaining`, ends the game once the endgame starts,
+/// taking the value of every tile still in the hand off the score.
+fn end_if_endgame(state: &mut GameState) -> Option<EventResponse> {
+    if !state.rules.contains(Mutator::DumpRemaining) || !state.is_endgame() {
+        return None;
+    }
+
+    let penalty: i64 = state
+        .hand
+        .tiles()
+        .iter()
+        .map(|tile: &char| state.scoring.points(*tile))
+        .sum();
+    state.change_score(-penalty);
+    finish(state);
+    Some(EventResponse::ChangeStatus(
+        format!("Endgame! {penalty} points of tiles were left in your hand.")
+            .set_style(Style::new().fg(Color::Yellow)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, HintBudget, apply};
+    use crate::{
+        GameState,
+        config::Config,
+        dictionary::{Dictionary, Normalization},
+    };
+
+    #[test]
+    fn test_hint_budget() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 3);
+        let word: String = state.hand.tiles()[..3].iter().collect();
+        state.dictionary = Dictionary::new([word], Normalization::default());
+        state.hints = HintBudget {
+            per_game: 2,
+            cost_percent: 10,
+        };
+        state.start();
+        state.score = 100;
+
+        // Each hint takes its share of the score as it stands.
+        apply(&mut state, Action::Hint).unwrap();
+        assert_eq!(state.score, 90);
+        apply(&mut state, Action::Hint).unwrap();
+        assert_eq!(state.score, 81);
+
+        // Once they're spent, asking again does nothing.
+        apply(&mut state, Action::Hint).unwrap();
+        assert_eq!(state.score, 81);
+        assert_eq!(state.hints_used(), 2);
+    }
+}
@@ -0,0 +1,539 @@
+//! Sizing up hands and boards.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dictionary::{BLANK, Dictionary, is_played_blank},
+    grid::{BoardStats, Coordinate, Grid, Orientation, Word},
+    rules::Rules,
+    scoring::Scoring,
+    solver,
+    util::is_vowel,
+};
+
+/// Letters that are hard to find a home for.
+const RARE_LETTERS: [char; 4] = ['j', 'q', 'x', 'z'];
+
+/// How hard a dealt hand looks to play out.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Difficulty {
+    /// The fraction of the hand that's vowels.
+    pub vowel_ratio: f64,
+    /// How many of the hand's tiles are rare letters.
+    pub rare_letters: usize,
+    /// The length of the longest word the hand can spell on its own.
+    pub longest_word: usize,
+}
+
+/// A rough label for a `Difficulty`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DifficultyRating {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// Looks over a hand, checking which dictionary words it could spell.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    pub fn of(hand: &[char], dictionary: &Dictionary) -> Self {
+        // Counted into an array rather than with `count_tiles`, since this runs for every
+        // word in the dictionary.
+        let letters: Letters = Letters::of(hand);
+        let counts: Vec<usize> = letters.count(hand);
+        let spellable = |word: &str| -> bool {
+            let mut left: Vec<usize> = counts.clone();
+            word.chars().all(|x: char| match letters.index(x) {
+                Some(index) if left[index] > 0 => {
+                    left[index] -= 1;
+                    true
+                }
+                _ => false,
+            })
+        };
+
+        Self {
+            vowel_ratio: hand.iter().filter(|x: &&char| is_vowel(**x)).count() as f64
+                / hand.len().max(1) as f64,
+            rare_letters: hand
+                .iter()
+                .filter(|x: &&char| RARE_LETTERS.contains(x))
+                .count(),
+            longest_word: dictionary
+                .words()
+                .filter(|word: &&String| word.chars().count() <= hand.len() && spellable(word))
+                .map(|word: &String| word.chars().count())
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// How far the hand is from an easy one. Lower is easier.
+    pub fn penalty(&self) -> f64 {
+        // About two in five tiles being vowels is the easiest mix to work with.
+        let balance: f64 = (self.vowel_ratio - 0.4).abs() * 10.0;
+        let rare: f64 = 1.5 * f64::from(u8::try_from(self.rare_letters).unwrap_or(u8::MAX));
+        let feasibility: f64 = match self.longest_word {
+            0..=3 => 3.0,
+            4 => 1.5,
+            _ => 0.0,
+        };
+        balance + rare + feasibility
+    }
+
+    pub fn rating(&self) -> DifficultyRating {
+        match self.penalty() {
+            x if x < 2.0 => DifficultyRating::Easy,
+            x if x < 4.0 => DifficultyRating::Medium,
+            _ => DifficultyRating::Hard,
+        }
+    }
+}
+
+impl Display for DifficultyRating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Easy => write!(f, "Easy"),
+            Self::Medium => write!(f, "Medium"),
+            Self::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+/// The letters among some tiles, in order, so that counts of them can be kept in a plain array.
+///
+/// Blanks aren't letters, played or not.
+struct Letters(Vec<char>);
+
+impl Letters {
+    fn of<'a>(tiles: impl IntoIterator<Item = &'a char>) -> Self {
+        let mut letters: Vec<char> = tiles
+            .into_iter()
+            .copied()
+            .filter(|x: &char| *x != BLANK && !is_played_blank(*x))
+            .collect();
+        letters.sort_unstable();
+        letters.dedup();
+        Self(letters)
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Where a letter's count is kept, if it's one of these letters.
+    fn index(&self, letter: char) -> Option<usize> {
+        self.0.binary_search(&letter).ok()
+    }
+
+    /// Counts how many of each of these letters there are among some tiles.
+    fn count<'a>(&self, tiles: impl IntoIterator<Item = &'a char>) -> Vec<usize> {
+        let mut counts: Vec<usize> = vec![0; self.len()];
+        for index in tiles.into_iter().filter_map(|x: &char| self.index(*x)) {
+            counts[index] += 1;
+        }
+        counts
+    }
+}
+
+/// How many of each letter a word needs, if there are enough of them between the hand and the board.
+///
+/// It has to use a tile from the hand, and cross the board somewhere if there's a board.
+fn playable(
+    word: &str,
+    letters: &Letters,
+    in_hand: &[usize],
+    on_board: &[usize],
+) -> Option<Vec<usize>> {
+    let mut needed: Vec<usize> = vec![0; letters.len()];
+    for letter in word.chars() {
+        needed[letters.index(letter)?] += 1;
+    }
+    let board_empty: bool = on_board.iter().all(|x: &usize| *x == 0);
+    let indices = || 0..letters.len();
+    (indices().all(|x: usize| needed[x] <= in_hand[x] + on_board[x])
+        && indices().any(|x: usize| needed[x] > 0 && in_hand[x] > 0)
+        && (board_empty || indices().any(|x: usize| needed[x] > 0 && on_board[x] > 0)))
+    .then_some(needed)
+}
+
+/// The letters in the hand that no word could use, however the board was laid out.
+///
+/// A blank could be anything, so a hand with one in it has no dead weight.
+pub fn dead_weight(hand: &[char], grid: &Grid<Option<char>>, dictionary: &Dictionary) -> Vec<char> {
+    if hand.contains(&BLANK) {
+        return Vec::new();
+    }
+    let on_board = || grid.occupied_cells().filter_map(|(_, x)| x.as_ref());
+    let letters: Letters = Letters::of(hand.iter().chain(on_board()));
+    let in_hand: Vec<usize> = letters.count(hand);
+    let on_board: Vec<usize> = letters.count(on_board());
+
+    let mut useful: Vec<bool> = vec![false; letters.len()];
+    for needed in dictionary
+        .words()
+        .filter_map(|word: &String| playable(word, &letters, &in_hand, &on_board))
+    {
+        for (useful, needed) in useful.iter_mut().zip(needed) {
+            *useful |= needed > 0;
+        }
+    }
+    let mut dead: Vec<char> = hand
+        .iter()
+        .copied()
+        .filter(|x: &char| letters.index(*x).is_some_and(|x: usize| !useful[x]))
+        .collect();
+    dead.sort_unstable();
+    dead.dedup();
+    dead
+}
+
+/// A hand the solver couldn't find a simple placement for, and the tile it's best to
+/// trade in.
+///
+/// The solver only lays a word down across a single tile, so a hand that can only
+/// extend a word or fill a gap looks stuck to it too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stuck {
+    /// The tile in the hand that the fewest words use.
+    pub dump: char,
+}
+
+impl Stuck {
+    /// Checks whether the solver can find a simple placement for a word from the hand.
+    pub fn check(
+        hand: &[char],
+        grid: &Grid<Option<char>>,
+        dictionary: &Dictionary,
+        scoring: &Scoring,
+        rules: &Rules,
+    ) -> Option<Self> {
+        // A blank can always go somewhere.
+        if hand.is_empty() || hand.contains(&BLANK) {
+            return None;
+        }
+        if solver::suggest(
+            hand,
+            grid,
+            dictionary,
+            scoring,
+            rules,
+            Coordinate::default(),
+        )
+        .is_some()
+        {
+            return None;
+        }
+
+        // How many words use each letter in the hand, counted in one go through the dictionary.
+        let mut uses: HashMap<char, usize> = hand.iter().map(|x: &char| (*x, 0)).collect();
+        for word in dictionary.words() {
+            for (letter, count) in &mut uses {
+                *count += usize::from(word.contains(*letter));
+            }
+        }
+        uses.into_iter()
+            .min_by_key(|&(letter, count)| (count, letter))
+            .map(|(dump, ..)| Self { dump })
+    }
+}
+
+/// How nice a board looks, which doesn't count towards the real score.
+///
+/// Each part is a fraction from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StyleScore {
+    /// How much of the board's bounding box is filled in.
+    pub compactness: f64,
+    /// How much of the board is mirrored across its middle, whichever way is better.
+    pub symmetry: f64,
+    /// How many tiles are part of both an across and a down word.
+    pub intersections: f64,
+}
+
+impl StyleScore {
+    /// Scores a board, or returns `None` if it's empty.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn of(grid: &Grid<Option<char>>, words: &[Word]) -> Option<Self> {
+        let (min, max) = grid.bounding_box()?;
+        let tiles: Vec<Coordinate> = grid
+            .occupied_cells()
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        let count: f64 = tiles.len() as f64;
+
+        let area: f64 = (f64::from(max.0) - f64::from(min.0) + 1.0)
+            * (f64::from(max.1) - f64::from(min.1) + 1.0);
+
+        // Mirroring inside the bounding box can't leave it, so the casts can't overflow.
+        #[allow(clippy::cast_possible_truncation)]
+        let mirrored = |mirror: &dyn Fn(Coordinate) -> (i64, i64)| -> f64 {
+            tiles
+                .iter()
+                .filter(|coordinate: &&Coordinate| {
+                    let (x, y) = mirror(**coordinate);
+                    grid[Coordinate(x as i32, y as i32)].is_some()
+                })
+                .count() as f64
+                / count
+        };
+        let symmetry: f64 = mirrored(&|Coordinate(x, y)| {
+            (
+                i64::from(min.0) + i64::from(max.0) - i64::from(x),
+                i64::from(y),
+            )
+        })
+        .max(mirrored(&|Coordinate(x, y)| {
+            (
+                i64::from(x),
+                i64::from(min.1) + i64::from(max.1) - i64::from(y),
+            )
+        }));
+
+        let covered = |orientation: Orientation| -> HashSet<Coordinate> {
+            words
+                .iter()
+                .filter(|word: &&Word| word.orientation == orientation && word.text.len() > 1)
+                .flat_map(|word: &Word| {
+                    let step: Coordinate = match orientation {
+                        Orientation::Horizontal => Coordinate(1, 0),
+                        Orientation::Vertical => Coordinate(0, -1),
+                    };
+                    (0..word.text.chars().count()).scan(word.start, move |position, _| {
+                        let current: Coordinate = *position;
+                        *position += step;
+                        Some(current)
+                    })
+                })
+                .collect()
+        };
+        let intersections: usize = covered(Orientation::Horizontal)
+            .intersection(&covered(Orientation::Vertical))
+            .count();
+
+        Some(Self {
+            compactness: count / area,
+            symmetry,
+            intersections: intersections as f64 / count,
+        })
+    }
+
+    /// All three parts rolled into a score out of 100.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn total(&self) -> u8 {
+        ((self.compactness + self.symmetry + self.intersections) / 3.0 * 100.0).round() as u8
+    }
+}
+
+/// A board spread out far more than its words need, with how much room they'd need instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sprawl {
+    /// The width and height of the board's bounding box.
+    pub dimensions: (u64, u64),
+    /// The side of a square the board could roughly be squeezed into.
+    pub tighter: u64,
+}
+
+impl Sprawl {
+    /// How many times more room than needed a board can take before it counts as sprawling.
+    const FACTOR: u64 = 3;
+
+    /// Checks whether the board's bounding box is far bigger than its tiles need.
+    ///
+    /// A tidy crossword fills about half its bounding box, and has to be at least
+    /// as wide as its longest word, so that's what the board is measured against.
+    pub fn check(stats: &BoardStats) -> Option<Self> {
+        let (width, height) = stats.dimensions;
+        let longest: u64 = stats
+            .words
+            .iter()
+            .map(|word: &Word| word.text.chars().count() as u64)
+            .max()
+            .unwrap_or(1);
+
+        let tighter: u64 = (2 * stats.tiles as u64).isqrt().max(longest);
+        (width * height > Self::FACTOR * tighter * tighter).then_some(Self {
+            dimensions: stats.dimensions,
+            tighter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Difficulty, DifficultyRating, Sprawl, Stuck, StyleScore, dead_weight};
+    use crate::{
+        dictionary::{Dictionary, Normalization},
+        grid::{Coordinate, Grid},
+        rules::Rules,
+        scoring::Scoring,
+    };
+
+    #[test]
+    fn test_difficulty() {
+        let dictionary: Dictionary = Dictionary::new(
+            ["tone", "stone", "notes", "onsets", "zzz"].map(String::from),
+            Normalization::default(),
+        );
+
+        let easy: Difficulty =
+            Difficulty::of(&"stonesea".chars().collect::<Vec<char>>(), &dictionary);
+        assert_eq!(easy.longest_word, 6);
+        assert_eq!(easy.rare_letters, 0);
+        assert_eq!(easy.rating(), DifficultyRating::Easy);
+
+        let hard: Difficulty =
+            Difficulty::of(&"qxzjtone".chars().collect::<Vec<char>>(), &dictionary);
+        assert_eq!(hard.longest_word, 4);
+        assert_eq!(hard.rare_letters, 4);
+        assert_eq!(hard.rating(), DifficultyRating::Hard);
+    }
+
+    #[test]
+    fn test_stuck() {
+        let dictionary: Dictionary = Dictionary::new(
+            ["cat", "act", "at", "qi"].map(String::from),
+            Normalization::default(),
+        );
+        let mut grid: Grid<Option<char>> = Grid::default();
+        let check = |letters: &str, grid: &Grid<Option<char>>| -> Option<Stuck> {
+            let hand: Vec<char> = letters.chars().collect();
+            Stuck::check(
+                &hand,
+                grid,
+                &dictionary,
+                &Scoring::default(),
+                &Rules::default(),
+            )
+        };
+
+        assert_eq!(check("tca", &grid), None);
+        assert_eq!(check("zta", &grid), None);
+        // 'z' isn't in any word at all, so it's the one to trade in.
+        assert_eq!(check("zzq", &grid), Some(Stuck { dump: 'z' }));
+
+        // With an 'a' on the board, 'c' and 't' can go through it, but there's no 'i' for 'q'.
+        grid[Coordinate(0, 0)] = Some('a');
+        assert_eq!(check("ct", &grid), None);
+        assert_eq!(check("qc", &grid), Some(Stuck { dump: 'q' }));
+
+        // Hemmed in on every side, the 'a' can't take a word through it, even though "ct"
+        // would spell one with it.
+        for side in [
+            Coordinate(-1, 0),
+            Coordinate(1, 0),
+            Coordinate(0, 1),
+            Coordinate(0, -1),
+        ] {
+            grid[side] = Some('z');
+        }
+        assert_eq!(check("ct", &grid), Some(Stuck { dump: 'c' }));
+
+        // An 's' could make "cats", but the solver doesn't extend words.
+        let dictionary: Dictionary =
+            Dictionary::new(["cat", "cats"].map(String::from), Normalization::default());
+        let mut grid: Grid<Option<char>> = Grid::default();
+        for (x, letter) in (0..).zip("cat".chars()) {
+            grid[Coordinate(x, 0)] = Some(letter);
+        }
+        let stuck: Option<Stuck> = Stuck::check(
+            &['s'],
+            &grid,
+            &dictionary,
+            &Scoring::default(),
+            &Rules::default(),
+        );
+        assert_eq!(stuck, Some(Stuck { dump: 's' }));
+    }
+
+    #[test]
+    fn test_dead_weight() {
+        let dictionary: Dictionary = Dictionary::new(
+            ["cat", "act", "at", "qi"].map(String::from),
+            Normalization::default(),
+        );
+        let hand = |letters: &str| -> Vec<char> { letters.chars().collect() };
+        let mut grid: Grid<Option<char>> = Grid::default();
+
+        assert_eq!(dead_weight(&hand("catzq"), &grid, &dictionary), ['q', 'z']);
+        // A blank could make the 'q' a "qi".
+        assert!(dead_weight(&hand("catq_"), &grid, &dictionary).is_empty());
+
+        // There's an 'i' on the board for the 'q' now, but nothing for 'c' to cross.
+        grid[Coordinate(0, 0)] = Some('i');
+        assert_eq!(dead_weight(&hand("cqz"), &grid, &dictionary), ['c', 'z']);
+
+        // Letters past 'z' are weighed the same way.
+        let dictionary: Dictionary = Dictionary::new(
+            ["año", "ñu", "él"].map(String::from),
+            Normalization::default(),
+        );
+        let grid: Grid<Option<char>> = Grid::default();
+        assert_eq!(dead_weight(&hand("añoé"), &grid, &dictionary), ['é']);
+    }
+
+    #[test]
+    fn test_style_score() {
+        let mut grid: Grid<Option<char>> = Grid::default();
+        assert_eq!(StyleScore::of(&grid, &[]), None);
+
+        // A plus sign is symmetrical, but sparse, with only its middle crossed.
+        // . c .
+        // c a t
+        // . t .
+        for (coordinate, letter) in [
+            (Coordinate(1, 1), 'c'),
+            (Coordinate(0, 0), 'c'),
+            (Coordinate(1, 0), 'a'),
+            (Coordinate(2, 0), 't'),
+            (Coordinate(1, -1), 't'),
+        ] {
+            grid[coordinate] = Some(letter);
+        }
+        let style: StyleScore = StyleScore::of(&grid, &grid.scan_for_words()).unwrap();
+        assert!((style.compactness - 5.0 / 9.0).abs() < f64::EPSILON);
+        assert!((style.symmetry - 1.0).abs() < f64::EPSILON);
+        assert!((style.intersections - 1.0 / 5.0).abs() < f64::EPSILON);
+        assert_eq!(style.total(), 59);
+
+        // Knocking off an arm breaks the left-right symmetry, but not the top-bottom.
+        grid[Coordinate(2, 0)] = None;
+        let style: StyleScore = StyleScore::of(&grid, &grid.scan_for_words()).unwrap();
+        assert!((style.symmetry - 1.0).abs() < f64::EPSILON);
+        grid[Coordinate(1, -1)] = None;
+        let style: StyleScore = StyleScore::of(&grid, &grid.scan_for_words()).unwrap();
+        assert!((style.symmetry - 2.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_sprawl() {
+        let mut grid: Grid<Option<char>> = Grid::default();
+        assert_eq!(Sprawl::check(&grid.stats()), None);
+
+        // Two words crossing in the middle are as tight as they get.
+        for (index, letter) in "stone".chars().enumerate() {
+            let offset: i32 = i32::try_from(index).unwrap();
+            grid[Coordinate(offset, 0)] = Some(letter);
+            grid[Coordinate(2, 2 - offset)] = Some(letter);
+        }
+        grid[Coordinate(2, 0)] = Some('o');
+        assert_eq!(Sprawl::check(&grid.stats()), None);
+
+        // A staircase of short words takes up a lot of room for its tiles.
+        let mut grid: Grid<Option<char>> = Grid::default();
+        for step in 0..8 {
+            for offset in 0..3 {
+                grid[Coordinate(2 * step + offset, -2 * step)] = Some('a');
+                grid[Coordinate(2 * step + 2, -2 * step - offset)] = Some('a');
+            }
+        }
+        let sprawl: Sprawl = Sprawl::check(&grid.stats()).unwrap();
+        assert_eq!(sprawl.dimensions, (17, 17));
+        assert_eq!(sprawl.tighter, 8);
+    }
+}
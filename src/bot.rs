@@ -0,0 +1,130 @@
+//! A computer opponent, building its own board from the same pile.
+//!
+//! The bot's words never touch the player's board, so they don't go through
+//! `action::apply`. Only its peels do, since they make the player draw, and
+//! those are recorded as `Action::BotPeel` like any other move.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dictionary::Dictionary,
+    grid::{Coordinate, Grid, Word},
+    rules::Rules,
+    scoring::Scoring,
+    solver::{self, Hint},
+    tiles::Hand,
+};
+
+/// What the bot did with its turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Turn {
+    /// Put a word down.
+    Played(Word),
+    /// Emptied its hand, and wants to peel.
+    Peel,
+    /// Couldn't find anywhere to put anything.
+    Stuck,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bot {
+    pub grid: Grid<Option<char>>,
+    pub hand: Hand,
+    /// How long the bot takes over each word.
+    pub delay: Duration,
+    /// How far into the game the bot makes its next move.
+    pub next_move: Duration,
+    /// How many times the bot has peeled.
+    pub peels: usize,
+    /// How far into the game the bot last peeled.
+    pub last_peel: Option<Duration>,
+    /// Whether the bot's last turn found nothing to play.
+    pub stuck: bool,
+}
+
+impl Bot {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            next_move: delay,
+            ..Self::default()
+        }
+    }
+
+    /// Clears the board for a new deal.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.delay);
+    }
+
+    /// Whether it's time for the bot's next move.
+    pub fn is_due(&self, elapsed: Duration) -> bool {
+        elapsed >= self.next_move
+    }
+
+    /// Takes a turn: the best word the solver can find, or a peel once the hand's empty.
+    pub fn play(&mut self, dictionary: &Dictionary, scoring: &Scoring, rules: &Rules) -> Turn {
+        if self.hand.is_empty() {
+            return Turn::Peel;
+        }
+        let Some(hint) = solver::suggest(
+            self.hand.tiles(),
+            &self.grid,
+            dictionary,
+            scoring,
+            rules,
+            Coordinate::default(),
+        ) else {
+            self.stuck = true;
+            return Turn::Stuck;
+        };
+
+        let Hint { word, placements } = hint;
+        for (coordinate, tile) in placements {
+            self.hand.take(tile);
+            self.grid[coordinate] = Some(tile);
+        }
+        self.stuck = false;
+        Turn::Played(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Bot, Turn};
+    use crate::{
+        dictionary::{Dictionary, Normalization},
+        rules::Rules,
+        scoring::Scoring,
+    };
+
+    #[test]
+    fn test_bot_turns() {
+        let dictionary: Dictionary =
+            Dictionary::new(["cat", "taxi"].map(String::from), Normalization::default());
+        let mut bot: Bot = Bot::new(Duration::from_secs(5));
+        assert!(!bot.is_due(Duration::from_secs(4)));
+        assert!(bot.is_due(Duration::from_secs(5)));
+        for tile in "catqaxi".chars() {
+            bot.hand.draw(tile);
+        }
+        let mut turn = || bot.play(&dictionary, &Scoring::default(), &Rules::default());
+
+        // "taxi" goes down first, then "cat" through its 't'.
+        assert!(matches!(turn(), Turn::Played(word) if word.text == "taxi"));
+        assert!(matches!(turn(), Turn::Played(word) if word.text == "cat"));
+        // Nothing uses the 'q', so the bot can't go out.
+        assert_eq!(turn(), Turn::Stuck);
+        assert!(bot.stuck);
+        assert_eq!(bot.grid.occupied_cells().count(), 6);
+
+        bot.hand.take('q');
+        assert_eq!(
+            bot.play(&dictionary, &Scoring::default(), &Rules::default()),
+            Turn::Peel
+        );
+    }
+}
@@ -1,48 +1,344 @@
-use std::ops::AddAssign;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::AddAssign,
+    time::{Duration, Instant},
+};
 
 use ratatui::{
-    layout::Rect,
-    style::{Color, Style, Styled},
+    layout::{Position, Rect},
+    style::{Color, Modifier, Style, Styled},
     text::Line,
-    widgets::{Paragraph, Widget},
+    widgets::{Block, BorderType, Borders, Paragraph, Widget},
+};
+use serde::Deserialize;
+use unicode_width::UnicodeWidthChar;
+
+use crate::{
+    Error,
+    dictionary::Dictionary,
+    grid::{BoardHealth, BoardStats, Coordinate, Grid, Orientation},
+    rules::Rules,
 };
 
-use crate::grid::{Coordinate, Grid, GridIndex};
+/// How many columns each cell of the board takes up, wide enough for any tile.
+const CELL_WIDTH: u16 = 2;
+
+/// How many cells a plain view shows either side of the cursor, across and down.
+const PLAIN_VIEW: (i32, i32) = (16, 8);
 
 #[derive(Clone)]
 pub struct Camera {
     pub grid: Grid<Option<char>>,
     pub cursor: Coordinate,
+    /// The way the cursor last moved, to pick between two words crossing under it.
+    pub heading: Orientation,
+    /// The last scan of `grid`, refreshed whenever a tile is put down or picked up.
+    pub stats: BoardStats,
+    /// The last result of `check_health`, shown as the border color.
+    pub health: BoardHealth,
+    /// Tiles blamed by the last failed check, drawn in the invalid color until the board changes.
+    pub flagged: HashSet<Coordinate>,
+    /// Whether to point out bad words as they're made, instead of waiting for a peel.
+    pub assist: bool,
+    /// Tiles in words that wouldn't pass a peel right now, when assisting.
+    pub doubtful: HashSet<Coordinate>,
+    /// Where the last hint said to put tiles, drawn faintly on the empty cells until they're filled.
+    pub ghost: HashMap<Coordinate, char>,
+    /// The letter scoring triple this game, picked out wherever it's played.
+    pub hot: Option<char>,
+    /// Tiles being picked out to move together, if there are any.
+    pub selection: Option<Selection>,
+    /// The cell under the mouse pointer, if it's over the board.
+    pub hover: Option<Coordinate>,
+    /// The tile picked up from the hand with the mouse, waiting for a cell to go on.
+    pub held: Option<char>,
+    pub theme: Theme,
+    /// Whether to highlight the cursor's row and column.
+    pub crosshair: bool,
+    /// Whether to draw every tile the same, and only a small view of the board, for slow terminals.
+    pub plain: bool,
+    /// How long scanning the board has taken since the last frame, for `--profile-render`.
+    pub scan_time: Duration,
+    /// How long checking the board's health has taken since the last frame.
+    pub check_time: Duration,
     current_screen_space: Rect,
+    /// The cells drawn in the last frame, as a bottom-left and top-right corner.
+    current_view: (Coordinate, Coordinate),
+}
+
+/// A rectangle of tiles being moved together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Stretching from this corner to the cursor.
+    Marking(Coordinate),
+    /// Lifted, and following the cursor from where it was when they were lifted.
+    Lifted {
+        region: (Coordinate, Coordinate),
+        from: Coordinate,
+    },
+}
+
+impl Selection {
+    /// The cells to highlight, with the cursor where it is.
+    pub fn area(self, cursor: Coordinate) -> (Coordinate, Coordinate) {
+        match self {
+            Self::Marking(corner) => region(corner, cursor),
+            Self::Lifted { region, from } => {
+                let offset: Coordinate = cursor - from;
+                (region.0 + offset, region.1 + offset)
+            }
+        }
+    }
+}
+
+/// The bottom-left and top-right corners of the rectangle two opposite corners make.
+pub fn region(a: Coordinate, b: Coordinate) -> (Coordinate, Coordinate) {
+    (
+        Coordinate(a.0.min(b.0), a.1.min(b.1)),
+        Coordinate(a.0.max(b.0), a.1.max(b.1)),
+    )
+}
+
+/// How the cell under the cursor is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorStyle {
+    /// Black on white.
+    #[default]
+    Reverse,
+    Underline,
+    Blinking,
+    /// A foreground and background color.
+    Custom {
+        fg: Color,
+        bg: Color,
+    },
+}
+
+impl CursorStyle {
+    pub fn style(self) -> Style {
+        match self {
+            Self::Reverse => Style::new().fg(Color::Black).bg(Color::White),
+            Self::Underline => Style::new().add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
+            Self::Blinking => Style::new().add_modifier(Modifier::SLOW_BLINK | Modifier::REVERSED),
+            Self::Custom { fg, bg } => Style::new().fg(fg).bg(bg),
+        }
+    }
+
+    /// Cycles to the next cursor style.
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Reverse => Self::Underline,
+            Self::Underline => Self::Blinking,
+            Self::Blinking => Self::Custom {
+                fg: Color::Black,
+                bg: Color::Yellow,
+            },
+            Self::Custom { .. } => Self::Reverse,
+        }
+    }
+}
+
+/// The colors the board is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// The border of a board that would pass a peel.
+    pub valid: Color,
+    /// The border of a board with words that aren't all connected.
+    pub disconnected: Color,
+    /// The border of a board with bad words on it.
+    pub invalid: Color,
+    /// Tiles in bad words, with assist on.
+    pub doubtful: Color,
+    /// Tiles a hint suggests.
+    pub hint: Color,
+    /// Tiles of the hot letter.
+    pub hot: Color,
+    /// The background of tiles picked out to move.
+    pub selection: Color,
+    /// The cursor's row and column, when they're highlighted.
+    pub crosshair: Color,
+    /// The cell under the cursor, until it's cycled with Shift + V.
+    pub cursor: CursorStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            valid: Color::Green,
+            disconnected: Color::Yellow,
+            invalid: Color::Red,
+            doubtful: Color::Yellow,
+            hint: Color::Cyan,
+            hot: Color::Magenta,
+            selection: Color::Blue,
+            crosshair: Color::DarkGray,
+            cursor: CursorStyle::default(),
+        }
+    }
 }
 
 impl Camera {
     pub fn new(grid: Grid<Option<char>>) -> Self {
         Self {
+            stats: grid.stats(),
             grid,
             cursor: Coordinate::default(),
+            heading: Orientation::Horizontal,
+            health: BoardHealth::default(),
+            flagged: HashSet::new(),
+            assist: false,
+            doubtful: HashSet::new(),
+            ghost: HashMap::new(),
+            hot: None,
+            selection: None,
+            hover: None,
+            held: None,
+            theme: Theme::default(),
+            crosshair: false,
+            plain: false,
+            scan_time: Duration::ZERO,
+            check_time: Duration::ZERO,
             current_screen_space: Rect::default(),
+            current_view: (Coordinate::default(), Coordinate::default()),
         }
     }
 
-    pub fn put(&mut self, letter: char) -> bool {
-        if self.grid[self.cursor].is_some() {
+    /// Where the word under the cursor starts, and the way it reads, for picking it up in one go.
+    ///
+    /// Where two words cross, it's the one along the way the cursor last moved.
+    pub fn word_under_cursor(&self) -> Option<(Coordinate, Coordinate)> {
+        self.grid[self.cursor]?;
+        let occupied = |x: Option<Coordinate>| x.is_some_and(|x| self.grid[x].is_some());
+        let along = |orientation: Orientation| {
+            occupied(orientation.step(self.cursor, true))
+                || occupied(orientation.step(self.cursor, false))
+        };
+        let orientation: Orientation =
+            match (along(Orientation::Horizontal), along(Orientation::Vertical)) {
+                (true, false) => Orientation::Horizontal,
+                (false, true) => Orientation::Vertical,
+                _ => self.heading,
+            };
+
+        let mut start: Coordinate = self.cursor;
+        while let Some(previous) = orientation
+            .step(start, false)
+            .filter(|x: &Coordinate| self.grid[*x].is_some())
+        {
+            start = previous;
+        }
+        let direction: Coordinate = match orientation {
+            Orientation::Horizontal => Coordinate(1, 0),
+            Orientation::Vertical => Coordinate(0, -1),
+        };
+        Some((start, direction))
+    }
+
+    pub fn put(&mut self, coordinate: Coordinate, letter: char) -> bool {
+        if self.grid[coordinate].is_some() {
             return false;
         }
-        self.grid[self.cursor].get_or_insert(letter);
+        self.grid[coordinate].get_or_insert(letter);
+        self.rescan();
+        self.flagged.clear();
+        self.ghost.remove(&coordinate);
         true
     }
 
-    pub fn pick_up(&mut self) -> Option<char> {
-        let tile: Option<char> = self.grid[self.cursor];
-        self.grid[self.cursor] = None;
+    /// Takes every tile off the board, leaving the view where it is.
+    pub fn clear(&mut self) {
+        self.grid = Grid::default();
+        self.rescan();
+        self.health = BoardHealth::default();
+        self.flagged.clear();
+        self.doubtful.clear();
+        self.ghost.clear();
+        self.selection = None;
+    }
+
+    pub fn pick_up(&mut self, coordinate: Coordinate) -> Option<char> {
+        let tile: Option<char> = self.grid.remove(coordinate);
+        if tile.is_some() {
+            self.rescan();
+            self.flagged.clear();
+        }
         tile
     }
+
+    /// Moves every tile in a region by an offset, or none of them if any would land on another.
+    pub fn move_tiles(
+        &mut self,
+        region: (Coordinate, Coordinate),
+        offset: Coordinate,
+    ) -> Result<usize, Error> {
+        let moved: usize = self.grid.move_region(region, offset)?;
+        if moved > 0 {
+            self.rescan();
+            self.flagged.clear();
+        }
+        Ok(moved)
+    }
+
+    /// The cell drawn at a spot on the screen, if the board was drawn there in the last frame.
+    pub fn cell_at(&self, column: u16, row: u16) -> Option<Coordinate> {
+        let area: Rect = self.current_screen_space;
+        if !area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let (min, max) = self.current_view;
+        let cell: Coordinate = Coordinate(
+            min.0 + i32::from((column - area.x) / CELL_WIDTH),
+            max.1 - i32::from(row - area.y),
+        );
+        (cell.0 <= max.0 && cell.1 >= min.1).then_some(cell)
+    }
+
+    /// Scans the board again after a change.
+    fn rescan(&mut self) {
+        let started: Instant = Instant::now();
+        self.stats = self.grid.stats();
+        self.scan_time += started.elapsed();
+    }
+
+    /// Starts counting scan and check times again, once a frame's been drawn.
+    pub const fn reset_scan_times(&mut self) {
+        self.scan_time = Duration::ZERO;
+        self.check_time = Duration::ZERO;
+    }
+
+    /// Checks the cached scan against a dictionary.
+    ///
+    /// With assist on, this also picks out the tiles in bad words.
+    pub fn check_health(&mut self, dictionary: &Dictionary, rules: &Rules) {
+        let started: Instant = Instant::now();
+        let words: Result<(), guavagrams::Error> =
+            Grid::validate_words(&self.stats.words, dictionary)
+                .and_then(|()| rules.validate_words(&self.stats.words));
+        self.doubtful = match &words {
+            Err(exception) if self.assist => exception.cells().iter().copied().collect(),
+            _ => HashSet::new(),
+        };
+        self.health = if words.is_err() {
+            BoardHealth::InvalidWords
+        } else if self.grid.validate_connectivity().is_err() {
+            BoardHealth::Disconnected
+        } else {
+            BoardHealth::Valid
+        };
+        self.check_time += started.elapsed();
+    }
 }
 
 impl AddAssign<Coordinate> for Camera {
     fn add_assign(&mut self, rhs: Coordinate) {
         self.cursor += rhs;
+        if rhs.0 != 0 {
+            self.heading = Orientation::Horizontal;
+        } else if rhs.1 != 0 {
+            self.heading = Orientation::Vertical;
+        }
     }
 }
 
@@ -52,37 +348,103 @@ impl Widget for &mut Camera {
     where
         Self: Sized,
     {
+        let block: Block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .border_style(Style::new().fg(match self.health {
+                BoardHealth::Valid => self.theme.valid,
+                BoardHealth::Disconnected => self.theme.disconnected,
+                BoardHealth::InvalidWords => self.theme.invalid,
+            }));
+        let area: Rect = {
+            let inner: Rect = block.inner(area);
+            block.render(area, buf);
+            inner
+        };
+
         self.current_screen_space = area;
         let text: Vec<Line> = {
             let mut output: Vec<Line> = Vec::new();
-            let cursor_index: GridIndex = self.cursor.into();
-            let clamped_y = cursor_index.1.clamp(
-                u8::MIN.saturating_add(area.height as u8 / 2),
-                u8::MAX.saturating_sub(area.height as u8 / 2),
-            );
-            let clamped_x = cursor_index.0.clamp(
-                u8::MIN.saturating_add(area.width as u8 / 4),
-                u8::MAX.saturating_sub(area.width as u8 / 4),
+            // The view follows the cursor, so it can scroll anywhere on the board.
+            let cursor: Coordinate = self.cursor;
+            let mut half_height: i32 = i32::from(area.height / 2);
+            let mut half_width: i32 = i32::from(area.width / (2 * CELL_WIDTH));
+            // Every cell that changes has to be sent, so a smaller view scrolls cheaper.
+            if self.plain {
+                half_width = half_width.min(PLAIN_VIEW.0);
+                half_height = half_height.min(PLAIN_VIEW.1);
+            }
+
+            let view: (Coordinate, Coordinate) = (
+                cursor - Coordinate(half_width, half_height),
+                cursor + Coordinate(half_width, half_height),
             );
 
-            for y in (clamped_y - (area.height / 2) as u8)..=(clamped_y + (area.height / 2) as u8) {
+            self.current_view = view;
+            let selected: Option<(Coordinate, Coordinate)> =
+                self.selection.map(|x: Selection| x.area(cursor));
+            for row in self.grid.rows_in(view) {
                 let mut line: Line = Line::default();
-                for x in clamped_x.saturating_sub((area.width / 4) as u8)
-                    ..=clamped_x.saturating_add((area.width / 4) as u8)
-                {
-                    let span = if GridIndex(x, y) == cursor_index {
-                        self.grid[GridIndex(x, y)]
-                            .unwrap_or('.')
-                            .to_string()
-                            .set_style(Style::new().fg(Color::Black).bg(Color::White))
+                for (coordinate, tile) in row {
+                    let Coordinate(x, y) = coordinate;
+                    let in_crosshair: bool =
+                        self.crosshair && !self.plain && (x == cursor.0 || y == cursor.1);
+                    let ghost: Option<char> = self
+                        .ghost
+                        .get(&coordinate)
+                        .copied()
+                        .filter(|_| tile.is_none());
+                    let hovered: bool = self.hover == Some(coordinate);
+                    // A tile held with the mouse shows where it would go.
+                    let preview: Option<char> = self.held.filter(|_| hovered && tile.is_none());
+                    let symbol: char = tile.or(preview).or(ghost).unwrap_or('.');
+                    let symbol_width: usize = symbol.width().unwrap_or(1);
+                    let symbol: String = symbol.to_string();
+                    let tile_style: Style = if self.plain {
+                        Style::default()
+                    } else if hovered && self.held.is_some() && tile.is_some() {
+                        Style::new()
+                            .fg(self.theme.invalid)
+                            .add_modifier(Modifier::UNDERLINED)
+                    } else if preview.is_some() {
+                        Style::new()
+                            .fg(self.theme.hint)
+                            .add_modifier(Modifier::UNDERLINED | Modifier::BOLD)
+                    } else if hovered {
+                        Style::new().add_modifier(Modifier::UNDERLINED)
+                    } else if ghost.is_some() {
+                        Style::new()
+                            .fg(self.theme.hint)
+                            .add_modifier(Modifier::DIM | Modifier::ITALIC)
+                    } else if self.flagged.contains(&coordinate) {
+                        Style::new().fg(self.theme.invalid)
+                    } else if self.doubtful.contains(&coordinate) {
+                        Style::new().fg(self.theme.doubtful)
+                    } else if tile.is_some() && tile == self.hot {
+                        Style::new().fg(self.theme.hot).add_modifier(Modifier::BOLD)
                     } else {
-                        self.grid[GridIndex(x, y)]
-                            .unwrap_or('.')
-                            .to_string()
-                            .set_style(Style::default())
+                        Style::default()
+                    };
+                    let span = if coordinate == cursor {
+                        symbol.set_style(self.theme.cursor.style())
+                    } else if selected.is_some_and(|(min, max)| {
+                        (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y)
+                    }) {
+                        symbol.set_style(tile_style.bg(self.theme.selection))
+                    } else if in_crosshair {
+                        symbol.set_style(tile_style.bg(self.theme.crosshair))
+                    } else {
+                        symbol.set_style(tile_style)
                     };
                     line.push_span(span);
-                    line.push_span(" ");
+                    let padding: String =
+                        " ".repeat(usize::from(CELL_WIDTH).saturating_sub(symbol_width));
+                    // Keep the crosshair's row unbroken.
+                    if in_crosshair && y == cursor.1 {
+                        line.push_span(padding.set_style(Style::new().bg(self.theme.crosshair)));
+                    } else {
+                        line.push_span(padding);
+                    }
                 }
                 output.push(line);
             }
@@ -92,3 +454,123 @@ impl Widget for &mut Camera {
         Paragraph::new(text).render(area, buf);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+    use super::Camera;
+    use crate::{
+        dictionary::{Dictionary, Normalization},
+        grid::{Coordinate, Grid},
+        rules::Rules,
+    };
+
+    #[test]
+    fn test_wide_tiles_stay_aligned() {
+        let mut camera: Camera = Camera::new(Grid::default());
+        camera.put(Coordinate(0, 1), 'a');
+        camera.put(Coordinate(1, 1), 'b');
+        camera.put(Coordinate(0, 0), '漢');
+        camera.put(Coordinate(1, 0), 'c');
+
+        let area: Rect = Rect::new(0, 0, 12, 6);
+        let mut buffer: Buffer = Buffer::empty(area);
+        camera.render(area, &mut buffer);
+
+        // The tile after a wide one starts in the same column as the tile above it.
+        let column = |symbol: &str| -> Option<u16> {
+            (0..area.height)
+                .flat_map(|y: u16| (0..area.width).map(move |x: u16| (x, y)))
+                .find(|&(x, y)| buffer[(x, y)].symbol() == symbol)
+                .map(|(x, ..)| x)
+        };
+        assert_eq!(column("b"), column("c"));
+        assert_eq!(column("a"), column("漢"));
+    }
+
+    #[test]
+    fn test_cell_at() {
+        let mut camera: Camera = Camera::new(Grid::default());
+        camera.put(Coordinate(1, 1), 'b');
+        let area: Rect = Rect::new(0, 0, 12, 6);
+        let mut buffer: Buffer = Buffer::empty(area);
+        camera.render(area, &mut buffer);
+
+        let (x, y) = (0..area.height)
+            .flat_map(|y: u16| (0..area.width).map(move |x: u16| (x, y)))
+            .find(|&(x, y)| buffer[(x, y)].symbol() == "b")
+            .unwrap();
+        assert_eq!(camera.cell_at(x, y), Some(Coordinate(1, 1)));
+        // The padding after a tile is still part of its cell.
+        assert_eq!(camera.cell_at(x + 1, y), Some(Coordinate(1, 1)));
+        assert_eq!(camera.cell_at(x, y + 1), Some(Coordinate(1, 0)));
+        // The border isn't on the board.
+        assert_eq!(camera.cell_at(0, 0), None);
+
+        // A tile held over an empty cell shows where it would go.
+        camera.held = Some('z');
+        camera.hover = Some(Coordinate(1, 0));
+        camera.render(area, &mut buffer);
+        assert_eq!(buffer[(x, y + 1)].symbol(), "z");
+    }
+
+    #[test]
+    fn test_assist() {
+        let dictionary: Dictionary =
+            Dictionary::new(["at"].map(String::from), Normalization::default());
+        let mut camera: Camera = Camera::new(Grid::default());
+        camera.put(Coordinate(0, 0), 'a');
+        camera.put(Coordinate(1, 0), 'x');
+        camera.check_health(&dictionary, &Rules::default());
+        assert!(camera.doubtful.is_empty());
+
+        camera.assist = true;
+        camera.check_health(&dictionary, &Rules::default());
+        assert_eq!(camera.doubtful.len(), 2);
+
+        // Fixing the word clears it up.
+        camera.pick_up(Coordinate(1, 0));
+        camera.put(Coordinate(1, 0), 't');
+        camera.check_health(&dictionary, &Rules::default());
+        assert!(camera.doubtful.is_empty());
+    }
+
+    #[test]
+    fn test_word_under_cursor() {
+        let mut camera: Camera = Camera::new(Grid::default());
+        // "cat" across, with "oat" down through its 'a'.
+        for (x, letter) in (0..).zip("cat".chars()) {
+            camera.put(Coordinate(x, 0), letter);
+        }
+        camera.put(Coordinate(1, 1), 'o');
+        camera.put(Coordinate(1, -1), 't');
+
+        camera.cursor = Coordinate(2, 0);
+        assert_eq!(
+            camera.word_under_cursor(),
+            Some((Coordinate(0, 0), Coordinate(1, 0)))
+        );
+        camera.cursor = Coordinate(1, -1);
+        assert_eq!(
+            camera.word_under_cursor(),
+            Some((Coordinate(1, 1), Coordinate(0, -1)))
+        );
+
+        // On the crossing, it follows the cursor.
+        camera += Coordinate(0, 1);
+        assert_eq!(
+            camera.word_under_cursor(),
+            Some((Coordinate(1, 1), Coordinate(0, -1)))
+        );
+        camera += Coordinate(1, 0);
+        camera += Coordinate(-1, 0);
+        assert_eq!(
+            camera.word_under_cursor(),
+            Some((Coordinate(0, 0), Coordinate(1, 0)))
+        );
+
+        camera.cursor = Coordinate(5, 5);
+        assert_eq!(camera.word_under_cursor(), None);
+    }
+}
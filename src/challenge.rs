@@ -0,0 +1,128 @@
+//! Weekly challenges: everyone playing in the same week gets the same seed and
+//! the same mutators, so their scores can be compared.
+//!
+//! The schedule is bundled with the game rather than fetched, and the seed is
+//! worked out from the week number, so challenges work offline and every copy
+//! of the game agrees on them.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{
+    rules::{Mutator, Rules},
+    util::{format_duration, mix},
+};
+
+/// The mutators for each week, in order. The schedule loops once it runs out.
+const SCHEDULE: [&[Mutator]; 6] = [
+    &[],
+    &[Mutator::NoLetter('s')],
+    &[Mutator::MinimumLength(4)],
+    &[Mutator::BonusLetter('q', 2.0)],
+    &[Mutator::NoLetter('s'), Mutator::MinimumLength(4)],
+    &[Mutator::MinimumLength(4), Mutator::BonusLetter('q', 2.0)],
+];
+
+/// A week's challenge.
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    /// Weeks since the first Monday after the Unix epoch.
+    pub week: u64,
+    pub seed: u64,
+    pub rules: Rules,
+}
+
+impl Challenge {
+    pub fn for_week(week: u64) -> Self {
+        Self {
+            week,
+            // Spread consecutive weeks out into unrelated seeds.
+            seed: mix(week),
+            rules: Rules {
+                mutators: SCHEDULE[usize::try_from(week).unwrap_or_default() % SCHEDULE.len()]
+                    .to_vec(),
+            },
+        }
+    }
+
+    /// This week's challenge, going by the system clock.
+    pub fn current() -> Self {
+        Self::for_week(week_of(SystemTime::now()))
+    }
+
+    /// The leaderboard this challenge's scores belong on.
+    pub fn bucket(&self) -> String {
+        format!("weekly-{}", self.week)
+    }
+
+    /// A line to paste somewhere, showing how a game of this challenge went.
+    pub fn share_string(&self, score: i64, time: Duration) -> String {
+        let mutators: String = if self.rules.mutators.is_empty() {
+            "no mutators".to_string()
+        } else {
+            self.rules
+                .mutators
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        format!(
+            "Guavagrams {} ({mutators}): {score} points in {}",
+            self.bucket(),
+            format_duration(time)
+        )
+    }
+}
+
+/// Which week a moment falls in. Weeks start on Monday, UTC.
+fn week_of(time: SystemTime) -> u64 {
+    let days: u64 = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400;
+    // The epoch was a Thursday, so the first Monday is four days later.
+    days.saturating_sub(4) / 7
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{Challenge, SCHEDULE, week_of};
+
+    #[test]
+    fn test_weeks_start_on_monday() {
+        // 1970-01-05 was the first Monday, and 2026-10-12 is a Monday too.
+        let monday: u64 = 4;
+        let later_monday: u64 = 20_738;
+        assert_eq!(week_of(UNIX_EPOCH + Duration::from_hours(24 * monday)), 0);
+        let week: u64 = week_of(UNIX_EPOCH + Duration::from_hours(24 * later_monday));
+        assert_eq!(
+            week_of(UNIX_EPOCH + Duration::from_hours(24 * later_monday - 1)),
+            week - 1
+        );
+        assert_eq!(
+            week_of(UNIX_EPOCH + Duration::from_hours(24 * (later_monday + 7) - 1)),
+            week
+        );
+    }
+
+    #[test]
+    fn test_challenges_are_stable() {
+        let challenge: Challenge = Challenge::for_week(100);
+        assert_eq!(challenge.seed, Challenge::for_week(100).seed);
+        assert_ne!(challenge.seed, Challenge::for_week(101).seed);
+        assert_eq!(
+            Challenge::for_week(100 + SCHEDULE.len() as u64)
+                .rules
+                .mutators,
+            challenge.rules.mutators
+        );
+        assert!(
+            Challenge::for_week(1)
+                .share_string(42, Duration::from_secs(90))
+                .starts_with("Guavagrams weekly-1 (No 's' tiles): 42 points in ")
+        );
+    }
+}
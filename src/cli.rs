@@ -0,0 +1,199 @@
+//! The command line.
+//!
+//! Anything set here beats the same setting in the config file.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::{Verbosity, config::Config, dictionary::Distribution};
+
+/// Bananagrams in the terminal.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Read settings from this file instead of the usual config file.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Play with this dictionary instead of picking one at startup. Give it more than once
+    /// to play with several, where a word from any of them counts.
+    #[arg(long, value_name = "FILE")]
+    pub dictionary: Vec<PathBuf>,
+    /// Deal from this set of tiles: bananagrams or scrabble.
+    #[arg(long)]
+    pub distribution: Option<Distribution>,
+    /// How many tiles a starting hand gets.
+    #[arg(long)]
+    pub hand_size: Option<usize>,
+    /// How many tiles go in the pile.
+    #[arg(long)]
+    pub pile_size: Option<usize>,
+    /// Deal from this seed, to play the same game as someone else. Weekly challenges have their own.
+    #[arg(long, conflicts_with = "import")]
+    pub seed: Option<u64>,
+    /// What kind of game to play.
+    #[arg(long, value_enum, default_value_t)]
+    pub mode: Mode,
+    /// How long a blitz game lasts, in minutes.
+    #[arg(long, default_value_t = 3)]
+    pub minutes: u64,
+    /// The score that ends a target game.
+    #[arg(long, default_value_t = 200)]
+    pub target: i64,
+    /// How many seconds the bot takes over each word.
+    #[arg(long, value_name = "SECONDS", default_value_t = 10)]
+    pub bot_delay: u64,
+    /// Step through an exported game.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["mode", "drill", "resume"])]
+    pub import: Option<PathBuf>,
+    /// Practice a random rack from a drill file.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["mode", "resume"])]
+    pub drill: Option<PathBuf>,
+    /// Pick a saved game to carry on with.
+    #[arg(long, conflicts_with = "mode")]
+    pub resume: bool,
+    /// Record the session as an asciicast file.
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+    /// Keep a strip of the score, time and word count in a file, for stream overlays.
+    #[arg(long, value_name = "FILE")]
+    pub score_cam: Option<PathBuf>,
+    /// Log how long each part of every frame takes to draw, as CSV.
+    #[arg(long, value_name = "FILE")]
+    pub profile_render: Option<PathBuf>,
+    /// Color in bad words as they're made, instead of waiting for a peel.
+    #[arg(long)]
+    pub assist: bool,
+    /// Click or drag tiles from the hand onto the board.
+    #[arg(long)]
+    pub mouse: bool,
+    /// Let modifiers be pressed before a key instead of with it.
+    #[arg(long)]
+    pub sticky_keys: bool,
+    /// Ignore a key pressed again within this many milliseconds.
+    #[arg(long, value_name = "MS", default_value_t = 0)]
+    pub debounce: u64,
+    /// How much gets announced in the status line: silent, important or everything.
+    #[arg(long, default_value = "important")]
+    pub verbosity: Verbosity,
+}
+
+/// Things to do instead of playing.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Look around the board in a save or an exported game, without playing it.
+    View {
+        /// The save or exported game to look at.
+        file: PathBuf,
+    },
+    /// Look through every word played so far, and how often.
+    Journal,
+    /// Work with dictionary files.
+    Dict {
+        #[command(subcommand)]
+        command: DictCommand,
+    },
+}
+
+/// Things to do with a dictionary file.
+#[derive(Debug, Subcommand)]
+pub enum DictCommand {
+    /// Print word lengths, letter frequencies, and the pile and scores a game would use.
+    Stats {
+        /// The dictionary to look at.
+        path: PathBuf,
+    },
+}
+
+/// The kinds of game that can be picked from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Mode {
+    /// A normal game.
+    #[default]
+    Normal,
+    /// A normal game, re-dealing hard starting hands.
+    Casual,
+    /// A game where the pile never runs out.
+    Endless,
+    /// A game against the clock.
+    Blitz,
+    /// A game that ends once the score reaches a target, with a pile that never runs out.
+    Target,
+    /// A race against a bot building its own board from the same pile.
+    Bot,
+    /// This week's challenge.
+    Weekly,
+    /// One of the starting hands that took longest to peel or needed the most trade-ins before.
+    Practice,
+}
+
+impl Cli {
+    /// Makes the config's settings give way to the command line's.
+    pub fn override_config(&self, config: &mut Config) {
+        if let Some((first, rest)) = self.dictionary.split_first() {
+            config.dictionary = Some(first.clone());
+            config.dictionaries = rest.to_vec();
+        }
+        if let Some(distribution) = &self.distribution {
+            config.distribution = distribution.clone();
+            // A different set means a different size of pile, unless one was asked for.
+            config.pile_size = None;
+        }
+        if let Some(hand_size) = self.hand_size {
+            config.hand_size = hand_size;
+        }
+        if let Some(pile_size) = self.pile_size {
+            config.pile_size = Some(pile_size);
+        }
+        config.assist |= self.assist;
+        config.mouse |= self.mouse;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, Parser};
+
+    use super::{Cli, Mode};
+    use crate::{config::Config, dictionary::Distribution};
+
+    #[test]
+    fn test_parse_args() {
+        Cli::command().debug_assert();
+
+        let cli: Cli = Cli::try_parse_from([
+            "guavagrams",
+            "--distribution",
+            "scrabble",
+            "--hand-size",
+            "15",
+            "--seed",
+            "42",
+            "--mode",
+            "blitz",
+        ])
+        .unwrap();
+        assert_eq!(cli.mode, Mode::Blitz);
+        assert_eq!(cli.seed, Some(42));
+
+        let mut config: Config = Config::default();
+        cli.override_config(&mut config);
+        assert!(matches!(config.distribution, Distribution::Scrabble));
+        assert_eq!(config.hand_size, 15);
+        assert_eq!(config.pile_size(), 100);
+
+        let cli: Cli =
+            Cli::try_parse_from(["guavagrams", "--mode", "target", "--target", "50"]).unwrap();
+        assert_eq!((cli.mode, cli.target), (Mode::Target, 50));
+        let cli: Cli =
+            Cli::try_parse_from(["guavagrams", "--mode", "bot", "--bot-delay", "4"]).unwrap();
+        assert_eq!((cli.mode, cli.bot_delay), (Mode::Bot, 4));
+
+        // Only one game can be picked.
+        assert!(Cli::try_parse_from(["guavagrams", "--mode", "weekly", "--resume"]).is_err());
+        assert!(Cli::try_parse_from(["guavagrams", "--distribution", "boggle"]).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+//! Settings read from a TOML file at startup.
+//!
+//! The file is `~/.config/guavagrams/config.toml` (or the same under
+//! `$XDG_CONFIG_HOME`), unless `--config <file>` points somewhere else. Every
+//! setting can be left out to keep its default, so a config can be as short as
+//! the one thing it changes:
+//!
+//! ```toml
+//! dictionary = "dictionaries/sowpods.csv"
+//! dictionaries = ["dictionaries/spanish.txt.gz"]
+//! distribution = "scrabble"
+//! hand_size = 15
+//! pile_size = 120
+//! scoring = "scores/spanish.toml"
+//! auto_peel = true
+//! auto_peel_grace_ms = 1000
+//! assist = true
+//! group_hand = true
+//! low_resource = true
+//! reduced_motion = true
+//! double_tap_ms = 250
+//! hot_letter = true
+//! mouse = true
+//! idle_minutes = 10
+//!
+//! [scores]
+//! q = 12
+//!
+//! [theme]
+//! valid = "blue"
+//! crosshair = "#303030"
+//! cursor = { custom = { fg = "black", bg = "yellow" } }
+//!
+//! [normalization]
+//! lowercase = false
+//!
+//! [hints]
+//! per_game = 5
+//! cost_percent = 5
+//!
+//! [timer]
+//! clock = "full"
+//! countdown = "compact"
+//! warning_secs = 30
+//! danger_secs = 5
+//!
+//! [double_tap]
+//! backspace = "clear_word"
+//! C = "check"
+//! ```
+//!
+//! While a game is running, the `[theme]` is read again whenever the file changes.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    Error,
+    action::HintBudget,
+    camera::Theme,
+    dictionary::{Distribution, Normalization},
+    keymap::{self, DoubleTap},
+    scoring::Scoring,
+    ui::Timer,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Config {
+    /// A dictionary to use instead of asking at startup.
+    pub dictionary: Option<PathBuf>,
+    /// More dictionaries to play alongside `dictionary`, where a word from any of them counts.
+    pub dictionaries: Vec<PathBuf>,
+    pub distribution: Distribution,
+    /// How words are tidied up when dictionaries are read.
+    pub normalization: Normalization,
+    /// How many tiles a starting hand gets.
+    pub hand_size: usize,
+    /// How many tiles go in the pile, or a whole set if left out.
+    pub pile_size: Option<usize>,
+    /// A table of letter scores to use, as a `.csv` or `.toml` file. See `scoring`.
+    pub scoring: Option<PathBuf>,
+    /// Points for letters that should score differently. Any letter left out keeps its usual score.
+    scores: HashMap<char, i64>,
+    /// The table read from `scoring`, once the config's been loaded.
+    #[serde(skip)]
+    table: Scoring,
+    pub theme: Theme,
+    pub timer: Timer,
+    /// Whether to peel as soon as the last tile in the hand is placed.
+    pub auto_peel: bool,
+    /// How long an auto-peel waits, so it can be called off.
+    pub auto_peel_grace_ms: u64,
+    /// Whether to color in bad words as they're made.
+    pub assist: bool,
+    /// Whether to show vowels and consonants apart in the hand, and pick out tiles no word could use.
+    pub group_hand: bool,
+    /// Whether to redraw less and keep the board plain, for slow terminals. It turns itself
+    /// on anyway if drawing is slow.
+    pub low_resource: bool,
+    /// Whether to keep things on screen from animating, like the score change fading out.
+    pub reduced_motion: bool,
+    /// How soon a key has to be pressed again to count as a double tap.
+    pub double_tap_ms: u64,
+    /// What double tapping a key does, by the key's name. Setting any replaces all the defaults.
+    pub double_tap: HashMap<String, DoubleTap>,
+    pub hints: HintBudget,
+    /// Whether a letter picked from the seed scores triple each game.
+    pub hot_letter: bool,
+    /// Whether tiles can be clicked or dragged from the hand onto the board.
+    pub mouse: bool,
+    /// How many minutes without input pause the game, or 0 to never pause it.
+    pub idle_minutes: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            dictionary: None,
+            dictionaries: Vec::new(),
+            distribution: Distribution::Bananagrams,
+            normalization: Normalization::default(),
+            hand_size: 21,
+            pile_size: None,
+            scoring: None,
+            scores: HashMap::new(),
+            table: Scoring::default(),
+            theme: Theme::default(),
+            timer: Timer::default(),
+            auto_peel: false,
+            auto_peel_grace_ms: 1500,
+            assist: false,
+            group_hand: false,
+            low_resource: false,
+            reduced_motion: false,
+            double_tap_ms: 300,
+            double_tap: HashMap::from([
+                ("backspace".to_string(), DoubleTap::ClearWord),
+                ("G".to_string(), DoubleTap::Check),
+            ]),
+            hints: HintBudget::default(),
+            hot_letter: false,
+            mouse: false,
+            idle_minutes: 5,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config from `path`, or from the usual place if there's no path.
+    ///
+    /// It's fine for there to be no config in the usual place, but not at a path that was asked for.
+    pub fn load(path: Option<&Path>) -> Result<Self, Error> {
+        let (path, required): (PathBuf, bool) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+        if !required && !path.exists() {
+            return Ok(Self::default());
+        }
+        let text: String = fs::read_to_string(&path)
+            .map_err(|exception| Error::ConfigFailed(exception.to_string()))?;
+        let mut config: Self = toml::from_str(&text)
+            .map_err(|exception| Error::ConfigFailed(exception.to_string()))?;
+        if let Some(scoring) = &config.scoring {
+            config.table = Scoring::load(scoring)?;
+        }
+        Ok(config)
+    }
+
+    /// Makes sure a game can be dealt with these settings.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.hand_size > self.pile_size() {
+            return Err(Error::HandTooBig(self.hand_size, self.pile_size()));
+        }
+        if let Some(name) = self
+            .double_tap
+            .keys()
+            .find(|name: &&String| keymap::parse_key(name).is_none())
+        {
+            return Err(Error::ConfigFailed(format!(
+                "\"{name}\" isn't a key that can be double tapped."
+            )));
+        }
+        Ok(())
+    }
+
+    /// Every letter's score, with the config's changes made.
+    pub fn scoring(&self) -> Scoring {
+        let mut scoring: Scoring = self.table.clone();
+        scoring.extend(&self.scores);
+        scoring
+    }
+
+    /// How many tiles go in the pile.
+    pub fn pile_size(&self) -> usize {
+        self.pile_size
+            .unwrap_or_else(|| self.distribution.set_size())
+    }
+}
+
+/// Reads the theme again whenever the config file changes, so colors can be tried out
+/// without restarting. Nothing else is taken from the new config.
+pub struct ThemeWatcher {
+    path: PathBuf,
+    /// When the file was last changed, as of the last look.
+    modified: Option<SystemTime>,
+    /// When the file was last looked at.
+    checked: Instant,
+}
+
+impl ThemeWatcher {
+    /// How often the file is looked at.
+    const INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Watches the config at `path`, or in the usual place if there's no path.
+    pub fn new(path: Option<&Path>) -> Option<Self> {
+        let path: PathBuf = path.map(Path::to_path_buf).or_else(default_path)?;
+        Some(Self {
+            modified: modified(&path),
+            path,
+            checked: Instant::now(),
+        })
+    }
+
+    /// The config's theme, if the file has changed since it was last looked at.
+    pub fn poll(&mut self) -> Option<Result<Theme, Error>> {
+        if self.checked.elapsed() < Self::INTERVAL {
+            return None;
+        }
+        self.checked = Instant::now();
+        let modified: Option<SystemTime> = modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(Config::load(Some(&self.path)).map(|x: Config| x.theme))
+    }
+}
+
+/// When a file was last changed, if it's there.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|x| x.modified()).ok()
+}
+
+/// Where the config is looked for when `--config` isn't given.
+fn default_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))
+        .map(|x: PathBuf| x.join("guavagrams").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        path::PathBuf,
+        time::{Duration, Instant, SystemTime},
+    };
+
+    use ratatui::style::Color;
+
+    use super::{Config, ThemeWatcher};
+    use crate::{camera::CursorStyle, dictionary::Distribution, util::TimerFormat};
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            "distribution = \"scrabble\"\nhand_size = 15\nidle_minutes = 0\n\n[scores]\nq = 12\n\n[theme]\nvalid = \"blue\"\ncursor = \"underline\"\n",
+        )
+        .unwrap();
+        assert!(matches!(config.distribution, Distribution::Scrabble));
+        assert_eq!(config.hand_size, 15);
+        assert_eq!(config.pile_size(), 100);
+        assert_eq!(config.idle_minutes, 0);
+        assert_eq!(config.scoring().points('q'), 12);
+        assert_eq!(config.scoring().points('z'), 10);
+        assert_eq!(config.theme.valid, Color::Blue);
+        assert_eq!(config.theme.invalid, Color::Red);
+        assert_eq!(config.theme.cursor, CursorStyle::Underline);
+        let config: Config =
+            toml::from_str("[theme]\ncursor = { custom = { fg = \"black\", bg = \"yellow\" } }\n")
+                .unwrap();
+        assert_eq!(
+            config.theme.cursor,
+            CursorStyle::Custom {
+                fg: Color::Black,
+                bg: Color::Yellow
+            }
+        );
+        let config: Config =
+            toml::from_str("[timer]\ncountdown = \"full\"\nwarning_secs = 30\n").unwrap();
+        assert_eq!(config.timer.countdown, TimerFormat::Full);
+        assert_eq!(config.timer.warning_secs, 30);
+        assert_eq!(config.timer.danger_secs, 10);
+        let config: Config = toml::from_str("[hints]\nper_game = 1\n").unwrap();
+        assert_eq!(config.hints.per_game, 1);
+        assert_eq!(config.hints.cost_percent, 10);
+        let config: Config = toml::from_str("[normalization]\nlowercase = false\n").unwrap();
+        assert!(!config.normalization.lowercase);
+        assert!(config.normalization.trim);
+
+        // An empty config is the same as none at all.
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.hand_size, 21);
+        assert_eq!(config.pile_size(), 144);
+        assert_eq!(config.idle_minutes, 5);
+
+        // Typos shouldn't be quietly ignored.
+        assert!(toml::from_str::<Config>("hand_sise = 15").is_err());
+        let config: Config = toml::from_str("[double_tap]\nhyper = \"check\"").unwrap();
+        assert!(config.check().is_err());
+    }
+
+    #[test]
+    fn test_theme_watcher() {
+        let path: PathBuf = std::env::temp_dir().join("guavagrams-theme-watcher.toml");
+        fs::write(&path, "[theme]\nvalid = \"blue\"\n").unwrap();
+        let mut watcher: ThemeWatcher = ThemeWatcher::new(Some(&path)).unwrap();
+        let look_again = |watcher: &mut ThemeWatcher| {
+            watcher.checked = Instant::now()
+                .checked_sub(ThemeWatcher::INTERVAL)
+                .unwrap_or(watcher.checked);
+        };
+
+        // Nothing's changed yet.
+        look_again(&mut watcher);
+        assert!(watcher.poll().is_none());
+
+        let file: File = File::options().write(true).open(&path).unwrap();
+        fs::write(&path, "[theme]\nvalid = \"magenta\"\n").unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        // Not until it's time to look again.
+        assert!(watcher.poll().is_none());
+        look_again(&mut watcher);
+        assert_eq!(watcher.poll().unwrap().unwrap().valid, Color::Magenta);
+        look_again(&mut watcher);
+        assert!(watcher.poll().is_none());
+        fs::remove_file(&path).unwrap();
+    }
+}
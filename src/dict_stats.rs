@@ -0,0 +1,98 @@
+//! A printed summary of a dictionary, for writing dictionaries and balancing scores.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use crate::{
+    config::Config,
+    dictionary::{Dictionary, Distribution, DistributionOptions, LetterDistribution},
+    rules::Rules,
+    scoring::Scoring,
+};
+
+/// How many characters wide the longest bar in a chart is.
+const BAR_WIDTH: usize = 40;
+
+/// Describes a dictionary: its word lengths, how often each letter comes up, and the
+/// pile and scores a game played with it would use.
+#[allow(clippy::cast_precision_loss)]
+pub fn report(dictionary: &Dictionary, config: &Config) -> String {
+    let mut output: String = String::new();
+    let words: usize = dictionary.words().count();
+    writeln!(&mut output, "{} ({words} words)", dictionary.name).unwrap();
+
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    for word in dictionary.words() {
+        *lengths.entry(word.chars().count()).or_default() += 1;
+    }
+    writeln!(&mut output, "\nWord lengths:").unwrap();
+    let most: usize = lengths.values().copied().max().unwrap_or_default();
+    for (length, count) in &lengths {
+        writeln!(&mut output, "{length:>3} {} {count}", bar(*count, most)).unwrap();
+    }
+
+    let distribution: Distribution =
+        Distribution::from_dictionary(dictionary, &DistributionOptions::default());
+    let Distribution::Dictionary { letters, .. } = &distribution else {
+        unreachable!()
+    };
+    let total: usize = letters.iter().map(|(.., x)| x).sum();
+    let most: usize = letters.iter().map(|(.., x)| *x).max().unwrap_or_default();
+    let pile: LetterDistribution = distribution.tile_counts(config.pile_size(), &Rules::default());
+    let scoring: Scoring = config.scoring();
+    writeln!(
+        &mut output,
+        "\nLetters (share of all letters, tiles in a {}-tile pile, points):",
+        config.pile_size()
+    )
+    .unwrap();
+    for (letter, count) in letters {
+        let tiles: usize = pile
+            .iter()
+            .find(|(x, ..)| x == letter)
+            .map_or(0, |(.., x)| *x);
+        let points: String = scoring
+            .get(*letter)
+            .map_or_else(|| "-".to_string(), |x: i64| x.to_string());
+        writeln!(
+            &mut output,
+            "  {letter} {:>5.2}% {:>3} {points:>3} {}",
+            *count as f64 / total.max(1) as f64 * 100.0,
+            tiles,
+            bar(*count, most)
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+/// A bar for a chart, as long as `value` is compared to `most`.
+fn bar(value: usize, most: usize) -> String {
+    "█".repeat(value * BAR_WIDTH / most.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::report;
+    use crate::{
+        config::Config,
+        dictionary::{Dictionary, Normalization},
+    };
+
+    #[test]
+    fn test_report() {
+        let mut dictionary: Dictionary = Dictionary::new(
+            ["cat", "tea", "at"].map(String::from),
+            Normalization::default(),
+        );
+        dictionary.name = "tiny".to_string();
+        let report: String = report(&dictionary, &Config::default());
+
+        assert!(report.starts_with("tiny (3 words)"));
+        // One two-letter word and twice as many three-letter ones.
+        assert!(report.contains(&format!("  2 {} 1\n", "█".repeat(20))));
+        assert!(report.contains(&format!("  3 {} 2\n", "█".repeat(40))));
+        // 't' and 'a' make up 3 of the 8 letters each, and score 1 point.
+        assert!(report.contains("  t 37.50%  54   1"));
+    }
+}
@@ -1,16 +1,21 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write as _},
     path::{Path, PathBuf},
-    sync::LazyLock,
+    str::FromStr,
+    sync::{LazyLock, OnceLock},
 };
 
 use csv::{Reader, StringRecord};
-use rand::{distr::Distribution as _, rngs::ThreadRng, seq::SliceRandom};
+use rand::{Rng, distr::Distribution as _, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
 use walkdir::{DirEntry, WalkDir};
 
-use crate::{Error, util::create_weights};
+use crate::{Error, rules::Rules, util::create_weights};
 
 /// Recursively lists every file in `./dictionaries/`.
+#[must_use]
 pub fn list_dictionaries() -> Vec<PathBuf> {
     WalkDir::new("dictionaries")
         .into_iter()
@@ -26,140 +31,675 @@ pub fn list_dictionaries() -> Vec<PathBuf> {
         .collect()
 }
 
-/// Reads a dictionary of words from a CSV file.
-pub fn get_dictionary(path: &Path) -> csv::Result<HashSet<String>> {
-    Ok(Reader::from_path(path)?
-        .into_records()
-        .map(|x: Result<StringRecord, csv::Error>| {
-            x.expect("Loading dictionary failed.")
-                .as_slice()
-                .to_string()
+/// Reads a dictionary of words from a file.
+///
+/// `.txt` files have a word to a line, and `.txt.gz` files are the same but gzipped,
+/// which is how most public word lists come. Anything else is read as a CSV.
+///
+/// # Errors
+///
+/// `Error::DictionaryFailed` if the file can't be read or isn't a word list.
+pub fn get_dictionary(path: &Path, normalization: Normalization) -> Result<Dictionary, Error> {
+    let mut dictionary: Dictionary = Dictionary::new(read_words(path)?, normalization);
+    // Leave off every extension, so "enable.txt.gz" is just "enable".
+    dictionary.name = path
+        .file_name()
+        .map_or_else(String::new, |x| x.to_string_lossy().into_owned())
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    Ok(dictionary)
+}
+
+/// Reads every word in a dictionary file, in whichever format its name says it's in.
+fn read_words(path: &Path) -> Result<Vec<String>, Error> {
+    let fail =
+        |exception: String| Error::DictionaryFailed(format!("{}: {exception}", path.display()));
+    let lines = |bytes: Vec<u8>| -> Result<Vec<String>, Error> {
+        let text: String = String::from_utf8(bytes).map_err(|x| fail(x.to_string()))?;
+        Ok(text.lines().map(String::from).collect())
+    };
+    match path.extension().and_then(|x| x.to_str()) {
+        Some("txt") => lines(fs::read(path).map_err(|x| fail(x.to_string()))?),
+        Some("gz") => {
+            lines(gunzip(&fs::read(path).map_err(|x| fail(x.to_string()))?).map_err(fail)?)
+        }
+        _ => Reader::from_path(path)
+            .map_err(|x| fail(x.to_string()))?
+            .into_records()
+            .map(|x: Result<StringRecord, csv::Error>| {
+                Ok(x.map_err(|x| fail(x.to_string()))?.as_slice().to_string())
+            })
+            .collect(),
+    }
+}
+
+/// Unpacks a gzipped file.
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    const TEXT: u8 = 1;
+    const HEADER_CRC: u8 = 2;
+    const EXTRA: u8 = 4;
+    const NAME: u8 = 8;
+    const COMMENT: u8 = 16;
+    let corrupt = || "it isn't a gzip file".to_string();
+
+    // The header's fixed part is 10 bytes, ending with a byte of flags for what follows it.
+    let [0x1f, 0x8b, 8, flags, ..] = *bytes else {
+        return Err(corrupt());
+    };
+    let mut rest: &[u8] = bytes.get(10..).ok_or_else(corrupt)?;
+    if flags & EXTRA != 0 {
+        let [low, high, ..] = *rest else {
+            return Err(corrupt());
+        };
+        rest = rest
+            .get(2 + usize::from(u16::from_le_bytes([low, high]))..)
+            .ok_or_else(corrupt)?;
+    }
+    // The original file name and a comment are each ended by a zero byte.
+    for flag in [NAME, COMMENT] {
+        if flags & flag != 0 {
+            let end: usize = rest.iter().position(|x: &u8| *x == 0).ok_or_else(corrupt)?;
+            rest = &rest[end + 1..];
+        }
+    }
+    if flags & HEADER_CRC != 0 {
+        rest = rest.get(2..).ok_or_else(corrupt)?;
+    }
+    // Only the text flag is fine to leave unread, since it's just a hint.
+    if flags & !(TEXT | HEADER_CRC | EXTRA | NAME | COMMENT) != 0 {
+        return Err(corrupt());
+    }
+    // The trailer is the unpacked file's CRC-32 and then its size, modulo 2^32.
+    let (deflated, trailer) = rest.split_last_chunk::<8>().ok_or_else(corrupt)?;
+    let unpacked: Vec<u8> =
+        miniz_oxide::inflate::decompress_to_vec(deflated).map_err(|x| x.to_string())?;
+    let [crc @ .., _, _, _, _] = *trailer;
+    let [_, _, _, _, size @ ..] = *trailer;
+    if u32::from_le_bytes(crc) != crc32(&unpacked) {
+        return Err("its checksum doesn't match, so it's probably corrupt".to_string());
+    }
+    // Truncating is how gzip stores the size, so anything over 4 GiB wraps around.
+    #[allow(clippy::cast_possible_truncation)]
+    if u32::from_le_bytes(size) != unpacked.len() as u32 {
+        return Err("it unpacks to the wrong size, so it's probably corrupt".to_string());
+    }
+    Ok(unpacked)
+}
+
+/// The CRC-32 gzip checks a file with.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |crc: u32, byte: &u8| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc: u32, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
         })
-        .collect())
+    })
 }
 
-pub type LetterDistribution = Vec<(char, usize)>;
+/// The most dictionaries that can be read into one, so every word can say which it came from.
+const MAX_SOURCES: usize = 32;
+
+/// Reads several dictionaries into one, so a word from any of them counts.
+///
+/// Handy for a base word list plus a file of extra words, or for playing in two
+/// languages at once. Each word remembers which of them it came from.
+///
+/// # Errors
+///
+/// `Error::DictionaryFailed` if any of them can't be read, or there are more than 32.
+pub fn get_dictionaries(
+    paths: &[PathBuf],
+    normalization: Normalization,
+) -> Result<Dictionary, Error> {
+    if paths.len() > MAX_SOURCES {
+        return Err(Error::DictionaryFailed(format!(
+            "only {MAX_SOURCES} dictionaries can be played at once"
+        )));
+    }
+    let mut merged: Dictionary = Dictionary::new(Vec::new(), normalization);
+    for (index, path) in paths.iter().enumerate() {
+        let dictionary: Dictionary = get_dictionary(path, normalization)?;
+        for word in dictionary.words.into_keys() {
+            *merged.words.entry(word).or_default() |= 1 << index;
+        }
+        merged.sources.push(dictionary.name);
+    }
+    merged.name = merged.sources.join(" + ");
+    Ok(merged)
+}
+
+/// Where the player's own words are kept, relative to the working directory.
+pub const HOUSE_PATH: &str = "saves/house.txt";
+
+/// Reads the house dictionary, one word to a line. It's fine for there not to be one.
+///
+/// # Errors
+///
+/// If the file is there but can't be read.
+pub fn load_house_words(path: &Path) -> io::Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(text.lines().map(String::from).collect()),
+        Err(exception) if exception.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(exception) => Err(exception),
+    }
+}
+
+/// Adds a word to the end of the house dictionary, making the file if there isn't one.
+///
+/// # Errors
+///
+/// If the file or its folder can't be written to.
+pub fn add_house_word(path: &Path, word: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file: fs::File = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{word}")
+}
+
+/// Counts the words in a dictionary file.
+///
+/// # Errors
+///
+/// `Error::DictionaryFailed` if the file can't be read.
+pub fn count_words(path: &Path) -> Result<usize, Error> {
+    Ok(read_words(path)?.len())
+}
+
+/// How words are tidied up before they're stored or looked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Normalization {
+    /// Compare words ignoring case.
+    pub lowercase: bool,
+    /// Ignore whitespace around words.
+    pub trim: bool,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            lowercase: true,
+            trim: true,
+        }
+    }
+}
+
+impl Normalization {
+    #[must_use]
+    pub fn apply(self, word: &str) -> String {
+        let word: &str = if self.trim { word.trim() } else { word };
+        if self.lowercase {
+            word.to_lowercase()
+        } else {
+            word.to_string()
+        }
+    }
+}
+
+/// A set of valid words.
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    /// What the dictionary's called, from its file name.
+    pub name: String,
+    /// Every word, with which of `sources` it came from, a bit for each.
+    words: HashMap<String, u32>,
+    /// The names of the dictionaries read into this one, if it's more than one file.
+    sources: Vec<String>,
+    normalization: Normalization,
+    /// The words again, letter by letter, for looking up what starts with what.
+    ///
+    /// Only built the first time it's needed, since most games never ask.
+    prefixes: OnceLock<Trie>,
+}
+
+impl Dictionary {
+    /// Creates a `Dictionary`, normalizing every word in it.
+    pub fn new(words: impl IntoIterator<Item = String>, normalization: Normalization) -> Self {
+        Self {
+            name: String::new(),
+            words: words
+                .into_iter()
+                .map(|word: String| normalization.apply(&word))
+                .filter(|word: &String| !word.is_empty())
+                .map(|word: String| (word, 0))
+                .collect(),
+            sources: Vec::new(),
+            normalization,
+            prefixes: OnceLock::new(),
+        }
+    }
 
+    /// Checks if a word is in the dictionary, after normalizing it the same way.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains_key(&self.normalization.apply(word))
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &String> {
+        self.words.keys()
+    }
+
+    /// The names of the dictionaries read into this one, in the order they were read.
+    #[must_use]
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    /// Which of the dictionaries read into this one have a word. Words added later,
+    /// like house words, aren't from any of them.
+    #[must_use]
+    pub fn sources_of(&self, word: &str) -> Vec<&str> {
+        let bits: u32 = self
+            .words
+            .get(&self.normalization.apply(word))
+            .copied()
+            .unwrap_or_default();
+        self.sources
+            .iter()
+            .enumerate()
+            .filter(|(index, ..)| bits & (1 << index) != 0)
+            .map(|(.., name)| name.as_str())
+            .collect()
+    }
+
+    /// How many of some words each of the dictionaries read into this one has, in order.
+    ///
+    /// A word in more than one counts for each of them.
+    #[must_use]
+    pub fn count_by_source<'a>(
+        &self,
+        words: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> =
+            self.sources.iter().map(|name| (name.as_str(), 0)).collect();
+        for word in words {
+            for source in self.sources_of(word) {
+                if let Some((.., count)) = counts.iter_mut().find(|(name, ..)| *name == source) {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Adds more words, normalizing them like the rest.
+    pub fn extend(&mut self, words: impl IntoIterator<Item = String>) {
+        let normalization: Normalization = self.normalization;
+        for word in words
+            .into_iter()
+            .map(|word: String| normalization.apply(&word))
+            .filter(|word: &String| !word.is_empty())
+        {
+            self.words.entry(word).or_default();
+        }
+        self.prefixes = OnceLock::new();
+    }
+
+    fn prefixes(&self) -> &Trie {
+        self.prefixes.get_or_init(|| {
+            let mut trie: Trie = Trie::default();
+            for word in self.words.keys() {
+                trie.insert(word);
+            }
+            trie
+        })
+    }
+
+    /// Checks if any word starts with `prefix`, after normalizing it.
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.prefixes()
+            .find(&self.normalization.apply(prefix))
+            .is_some()
+    }
+
+    /// Every word that starts with `prefix`, in order.
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        self.prefixes()
+            .completions(&self.normalization.apply(prefix))
+    }
+}
+
+/// Words stored as a tree of letters, where every word starting the same way shares a branch.
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
+struct Trie {
+    /// Every node, the root first.
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    /// The letters that can come next, in order, and the nodes they lead to.
+    children: Vec<(char, usize)>,
+    /// Whether the letters leading here spell a whole word.
+    word: bool,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self {
+            nodes: vec![TrieNode::default()],
+        }
+    }
+}
+
+impl Trie {
+    fn insert(&mut self, word: &str) {
+        let mut node: usize = 0;
+        for letter in word.chars() {
+            node = match self.nodes[node]
+                .children
+                .binary_search_by_key(&letter, |(x, ..)| *x)
+            {
+                Ok(index) => self.nodes[node].children[index].1,
+                Err(index) => {
+                    let child: usize = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].children.insert(index, (letter, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].word = true;
+    }
+
+    /// The node reached by following `prefix` from the root, if every letter of it leads somewhere.
+    fn find(&self, prefix: &str) -> Option<usize> {
+        prefix.chars().try_fold(0, |node: usize, letter: char| {
+            let children: &[(char, usize)] = &self.nodes[node].children;
+            children
+                .binary_search_by_key(&letter, |(x, ..)| *x)
+                .ok()
+                .map(|index: usize| children[index].1)
+        })
+    }
+
+    /// Every word that starts with `prefix`, in order.
+    fn completions(&self, prefix: &str) -> Vec<String> {
+        let mut found: Vec<String> = Vec::new();
+        let Some(start) = self.find(prefix) else {
+            return found;
+        };
+        // Children are pushed in reverse so they come off the stack in order.
+        let mut stack: Vec<(usize, String)> = vec![(start, prefix.to_string())];
+        while let Some((node, word)) = stack.pop() {
+            if self.nodes[node].word {
+                found.push(word.clone());
+            }
+            for (letter, child) in self.nodes[node].children.iter().rev() {
+                let mut next: String = word.clone();
+                next.push(*letter);
+                stack.push((*child, next));
+            }
+        }
+        found
+    }
+}
+
+pub type LetterDistribution = Vec<(char, usize)>;
+
+/// A tile that can stand in for any letter, but scores nothing.
+///
+/// Once it's on the board, a blank is kept as the uppercase letter it stands for.
+pub const BLANK: char = '_';
+
+/// The tile a blank is kept as on the board when it's played as `letter`.
+///
+/// That's the letter in uppercase, so letters without exactly one uppercase form of their
+/// own (like 'ß', or any letter from a script without case) can't have a blank played as them.
+#[must_use]
+pub fn blank_as(letter: char) -> Option<char> {
+    let mut upper = letter.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(tile), None) if tile != letter => Some(tile),
+        _ => None,
+    }
+}
+
+/// Checks if a tile on the board is a blank played as a letter.
+#[must_use]
+pub fn is_played_blank(tile: char) -> bool {
+    tile.is_uppercase()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Distribution {
     /// The distributed rarity of tiles from a dictionary.
-    Dictionary(LetterDistribution),
+    Dictionary {
+        letters: LetterDistribution,
+        /// The fewest tiles of each letter a pile gets.
+        minimum: usize,
+    },
     /// The standard Bananagrams tile distribution.
     Bananagrams,
+    /// The standard Scrabble tile distribution, blanks and all.
     Scrabble,
 }
 
+impl FromStr for Distribution {
+    type Err = Error;
+
+    /// Parses one of the standard sets. Dictionary distributions are made from a dictionary, not named.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bananagrams" => Ok(Self::Bananagrams),
+            "scrabble" => Ok(Self::Scrabble),
+            _ => Err(Error::UnknownDistribution(s.to_string())),
+        }
+    }
+}
+
 impl Distribution {
-    /// Creates a `Distribution::Dictionary` from a `HashSet`.
-    pub fn from_dictionary(dictionary: &HashSet<String>) -> Self {
+    /// Creates a `Distribution::Dictionary` from a `Dictionary`.
+    pub fn from_dictionary(dictionary: &Dictionary, options: &DistributionOptions) -> Self {
         let mut characters: Vec<char> = Vec::new();
-        for word in dictionary {
+        for word in dictionary.words() {
             characters.append(
                 &mut word
                     .chars()
-                    .filter(|character: &char| !character.is_whitespace())
+                    .filter(|character: &char| options.allows(*character))
                     .collect(),
             );
         }
         // Sort so that chunking works
         characters.sort_unstable();
-        Self::Dictionary(
-            characters
+        Self::Dictionary {
+            letters: characters
                 // Chunk all characters into seperate, smaller arrays
                 .chunk_by(|x: &char, y: &char| x == y)
                 // Map each `[x]` to `(x, count)`
                 .map(|x: &[char]| (x[0], x.len()))
                 .collect(),
-        )
+            minimum: options.minimum,
+        }
     }
 
-    #[allow(
-        clippy::cast_precision_loss,
-        clippy::cast_possible_truncation,
-        clippy::cast_sign_loss
-    )]
-    fn create_pile_internals(letter_distribution: &LetterDistribution, amount: usize) -> Vec<char> {
-        let mut output: Vec<char> = Vec::new();
-        let total_letters: f64 = letter_distribution
+    /// Splits `amount` tiles between letters in proportion to their frequencies.
+    ///
+    /// Uses the largest remainder method, so the counts always add up to exactly
+    /// `amount` (as long as there are any letters to split between).
+    /// `minimum` tiles of every letter are set aside first, if there are enough to go around.
+    fn apportion(
+        letter_distribution: &LetterDistribution,
+        amount: usize,
+        minimum: usize,
+    ) -> LetterDistribution {
+        let total_letters: usize = letter_distribution.iter().map(|(.., x)| x).sum();
+        if total_letters == 0 {
+            return Vec::new();
+        }
+        let minimum: usize = minimum.min(amount / letter_distribution.len());
+        let shared: usize = amount - minimum * letter_distribution.len();
+
+        // Give every letter the whole part of its share first...
+        let mut output: LetterDistribution = letter_distribution
+            .iter()
+            .map(|(tile, frequency)| (*tile, minimum + frequency * shared / total_letters))
+            .collect();
+        let mut leftover: usize = amount - output.iter().map(|(.., x)| x).sum::<usize>();
+
+        // ...then hand out what's left to the letters with the biggest remainders.
+        let mut remainders: Vec<(usize, usize)> = letter_distribution
             .iter()
-            .fold(0.0, |total: f64, (.., curr)| total + *curr as f64);
-        for (tile, frequency) in letter_distribution.clone() {
-            output.extend_from_slice(&vec![
-                tile;
-                (frequency as f64 / (total_letters / amount as f64)).round()
-                    as usize
-            ]);
-        }
-        output.shuffle(&mut ThreadRng::default());
+            .enumerate()
+            .map(|(index, (.., frequency))| (index, frequency * shared % total_letters))
+            .collect();
+        remainders.sort_by(|(a_index, a), (b_index, b)| b.cmp(a).then(a_index.cmp(b_index)));
+        for (index, ..) in remainders {
+            if leftover == 0 {
+                break;
+            }
+            output[index].1 += 1;
+            leftover -= 1;
+        }
+
         output
     }
 
-    pub fn create_pile(&self, amount: usize) -> Vec<char> {
+    /// Every kind of tile and how often it comes up.
+    fn table(&self) -> &LetterDistribution {
         match self {
-            Self::Dictionary(letter_distribution) => {
-                Self::create_pile_internals(letter_distribution, amount)
-            }
-            Self::Bananagrams => Self::create_pile_internals(&BANANAGRAMS, amount),
-            Self::Scrabble => todo!(),
+            Self::Dictionary { letters, .. } => letters,
+            Self::Bananagrams => &BANANAGRAMS,
+            Self::Scrabble => &SCRABBLE,
         }
     }
 
-    pub fn pull_from_pile(pile: &mut Vec<char>, amount: usize) -> Result<Vec<char>, Error> {
-        if pile.len() < amount {
-            return Err(Error::NoMoreTiles);
+    /// The letters and how often they come up, leaving out any the rules don't allow.
+    fn letters(&self, rules: &Rules) -> LetterDistribution {
+        self.table()
+            .iter()
+            .filter(|(tile, ..)| rules.allows_tile(*tile))
+            .copied()
+            .collect()
+    }
+
+    /// Works out how many of each tile a pile of `amount` tiles would have.
+    #[must_use]
+    pub fn tile_counts(&self, amount: usize, rules: &Rules) -> LetterDistribution {
+        let minimum: usize = match self {
+            Self::Dictionary { minimum, .. } => *minimum,
+            Self::Bananagrams | Self::Scrabble => 0,
+        };
+        Self::apportion(&self.letters(rules), amount, minimum)
+    }
+
+    pub fn create_pile(&self, amount: usize, rng: &mut impl Rng, rules: &Rules) -> Vec<char> {
+        let mut output: Vec<char> = self
+            .tile_counts(amount, rules)
+            .into_iter()
+            .flat_map(|(tile, count)| std::iter::repeat_n(tile, count))
+            .collect();
+        output.shuffle(rng);
+        output
+    }
+
+    /// Draws a tile from a pile that never runs out, weighted by how common each letter is.
+    pub fn pull_endless(&self, rng: &mut impl Rng, rules: &Rules) -> char {
+        let letters: LetterDistribution = self.letters(rules);
+        letters[create_weights(&letters).sample(rng)].0
+    }
+
+    /// How many tiles are in a whole set.
+    ///
+    /// Dictionary distributions don't come in sets, so they use a Bananagrams-sized pile.
+    #[must_use]
+    pub const fn set_size(&self) -> usize {
+        match self {
+            Self::Dictionary { .. } | Self::Bananagrams => 144,
+            Self::Scrabble => 100,
         }
-        Ok(pile.drain(..amount).collect())
     }
 
-    pub fn pull_endless(&self) -> char {
-        let mut rng: ThreadRng = ThreadRng::default();
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
         match self {
-            Self::Dictionary(letter_distribution) => {
-                letter_distribution[create_weights(letter_distribution).sample(&mut rng)].0
-            }
-            Self::Bananagrams => BANANAGRAMS[create_weights(&BANANAGRAMS).sample(&mut rng)].0,
-            Self::Scrabble => todo!(),
+            Self::Dictionary { .. } => "Dictionary",
+            Self::Bananagrams => "Bananagrams",
+            Self::Scrabble => "Scrabble",
         }
     }
 
+    /// Every letter a tile can have, not counting blanks.
+    ///
+    /// Anything else typed isn't a letter in this game, whatever alphabet it's from.
+    pub fn alphabet(&self) -> impl Iterator<Item = char> {
+        self.table()
+            .iter()
+            .map(|(tile, ..)| *tile)
+            .filter(|tile: &char| *tile != BLANK)
+    }
+
     /// Checks if the distribution contains a letter.
+    #[must_use]
     pub fn contains_letter(&self, letter: char) -> bool {
-        match self {
-            Self::Dictionary(items) => items.iter().any(|x| x.0 == letter),
-            Self::Bananagrams | Self::Scrabble => letter.is_alphabetic(),
-        }
-    }
-}
-
-/*const SCRABBLE: LetterDistribution = vec![
-    ('a'),
-    ('b'),
-    ('c'),
-    ('d'),
-    ('e'),
-    ('f'),
-    ('g'),
-    ('h'),
-    ('i'),
-    ('j'),
-    ('k'),
-    ('l'),
-    ('m'),
-    ('n'),
-    ('o'),
-    ('p'),
-    ('q'),
-    ('r'),
-    ('s'),
-    ('t'),
-    ('u'),
-    ('v'),
-    ('w'),
-    ('x'),
-    ('y'),
-    ('z'),
-];*/
+        self.alphabet().any(|x: char| x == letter)
+    }
+
+    /// Checks if the distribution deals a tile, blanks included.
+    #[must_use]
+    pub fn contains_tile(&self, tile: char) -> bool {
+        self.table().iter().any(|(x, ..)| *x == tile)
+    }
+}
+
+/// Rules for turning a dictionary into a `Distribution`.
+#[derive(Debug, Clone, Default)]
+pub struct DistributionOptions {
+    /// The fewest tiles of each letter a pile gets, so rare letters don't round away to nothing.
+    pub minimum: usize,
+    /// The only characters that can become tiles. By default, that's every letter.
+    pub allowed: Option<HashSet<char>>,
+    /// Characters that never become tiles.
+    pub excluded: HashSet<char>,
+}
+
+impl DistributionOptions {
+    /// Checks if a character can become a tile.
+    fn allows(&self, character: char) -> bool {
+        !self.excluded.contains(&character)
+            && self
+                .allowed
+                .as_ref()
+                .map_or_else(|| character.is_alphabetic(), |x| x.contains(&character))
+    }
+}
+
+static SCRABBLE: LazyLock<LetterDistribution> = LazyLock::new(|| {
+    vec![
+        ('a', 9),
+        ('b', 2),
+        ('c', 2),
+        ('d', 4),
+        ('e', 12),
+        ('f', 2),
+        ('g', 3),
+        ('h', 2),
+        ('i', 9),
+        ('j', 1),
+        ('k', 1),
+        ('l', 4),
+        ('m', 2),
+        ('n', 6),
+        ('o', 8),
+        ('p', 2),
+        ('q', 1),
+        ('r', 6),
+        ('s', 4),
+        ('t', 6),
+        ('u', 4),
+        ('v', 2),
+        ('w', 2),
+        ('x', 1),
+        ('y', 2),
+        ('z', 1),
+        (BLANK, 2),
+    ]
+});
 static BANANAGRAMS: LazyLock<LetterDistribution> = LazyLock::new(|| {
     vec![
         ('a', 13),
@@ -190,3 +730,245 @@ static BANANAGRAMS: LazyLock<LetterDistribution> = LazyLock::new(|| {
         ('z', 2),
     ]
 });
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{
+        BANANAGRAMS, BLANK, Dictionary, Distribution, DistributionOptions, LetterDistribution,
+        Normalization, add_house_word, blank_as, crc32, get_dictionaries, get_dictionary,
+        load_house_words,
+    };
+    use crate::rules::{Mutator, Rules};
+
+    fn total(counts: &LetterDistribution) -> usize {
+        counts.iter().map(|(.., x)| x).sum()
+    }
+
+    #[test]
+    fn test_exact_pile_sizes() {
+        // The standard set is 144 tiles, so asking for that gets exactly the standard set.
+        assert_eq!(
+            Distribution::Bananagrams.tile_counts(144, &Rules::default()),
+            *BANANAGRAMS
+        );
+
+        for amount in [0, 1, 7, 21, 100, 139, 150, 1000] {
+            assert_eq!(
+                total(&Distribution::Bananagrams.tile_counts(amount, &Rules::default())),
+                amount
+            );
+        }
+    }
+
+    #[test]
+    fn test_scrabble_set() {
+        let counts: LetterDistribution = Distribution::Scrabble.tile_counts(100, &Rules::default());
+        assert_eq!(total(&counts), 100);
+        assert!(counts.contains(&(BLANK, 2)));
+        assert!(counts.contains(&('e', 12)));
+        assert!(!Distribution::Scrabble.contains_letter(BLANK));
+        assert!(Distribution::Scrabble.contains_tile(BLANK));
+    }
+
+    #[test]
+    fn test_alphabets() {
+        assert_eq!(Distribution::Bananagrams.alphabet().count(), 26);
+        assert!(!Distribution::Bananagrams.contains_letter('é'));
+
+        // A dictionary's letters are its alphabet, whatever script they're in.
+        let dictionary: Dictionary = Dictionary::new(
+            ["café", "straße", "漢字"].map(String::from),
+            Normalization::default(),
+        );
+        let distribution: Distribution =
+            Distribution::from_dictionary(&dictionary, &DistributionOptions::default());
+        assert!(distribution.contains_letter('é'));
+        assert!(distribution.contains_letter('漢'));
+        assert!(!distribution.contains_letter('z'));
+
+        assert_eq!(blank_as('é'), Some('É'));
+        assert_eq!(blank_as('ß'), None);
+        assert_eq!(blank_as('漢'), None);
+    }
+
+    #[test]
+    fn test_largest_remainders_win() {
+        let distribution: Distribution = Distribution::Dictionary {
+            letters: vec![('a', 5), ('b', 3), ('c', 2)],
+            minimum: 0,
+        };
+        // Shares are 2.5, 1.5 and 1, so the tie goes to the first letter.
+        assert_eq!(
+            distribution.tile_counts(5, &Rules::default()),
+            vec![('a', 3), ('b', 1), ('c', 1)]
+        );
+        assert!(
+            Distribution::Dictionary {
+                letters: Vec::new(),
+                minimum: 0
+            }
+            .tile_counts(10, &Rules::default())
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_dictionary_options() {
+        let dictionary: Dictionary = Dictionary::new(
+            ["it's", "a-ok", "zoo", "tea", "eat", "ate", "eta"].map(String::from),
+            Normalization::default(),
+        );
+
+        // Punctuation is left out by default.
+        let Distribution::Dictionary { letters, .. } =
+            Distribution::from_dictionary(&dictionary, &DistributionOptions::default())
+        else {
+            unreachable!()
+        };
+        assert!(letters.iter().all(|(tile, ..)| tile.is_alphabetic()));
+
+        let options: DistributionOptions = DistributionOptions {
+            minimum: 2,
+            allowed: None,
+            excluded: HashSet::from(['k']),
+        };
+        let distribution: Distribution = Distribution::from_dictionary(&dictionary, &options);
+        assert!(!distribution.contains_letter('k'));
+        // 'z' is rare enough to round away to nothing without a minimum.
+        let counts: LetterDistribution = distribution.tile_counts(20, &Rules::default());
+        assert_eq!(total(&counts), 20);
+        assert!(counts.iter().all(|(.., x)| *x >= 2));
+    }
+
+    #[test]
+    fn test_mutators_shape_the_pile() {
+        let rules: Rules = Rules {
+            mutators: vec![Mutator::NoLetter('s')],
+        };
+        let counts: LetterDistribution = Distribution::Bananagrams.tile_counts(144, &rules);
+        assert_eq!(total(&counts), 144);
+        assert!(counts.iter().all(|(tile, ..)| *tile != 's'));
+
+        let mut rng: StdRng = StdRng::seed_from_u64(0);
+        assert!((0..500).all(|_| Distribution::Bananagrams.pull_endless(&mut rng, &rules) != 's'));
+    }
+
+    #[test]
+    fn test_house_words() {
+        let path =
+            std::env::temp_dir().join(format!("guavagrams-house-{}.txt", std::process::id()));
+        assert!(load_house_words(&path).unwrap().is_empty());
+
+        add_house_word(&path, "Yeet").unwrap();
+        add_house_word(&path, "bruv").unwrap();
+        let mut dictionary: Dictionary =
+            Dictionary::new(["cat"].map(String::from), Normalization::default());
+        dictionary.extend(load_house_words(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(dictionary.contains("cat"));
+        assert!(dictionary.contains("yeet"));
+        assert!(dictionary.contains("bruv"));
+    }
+
+    #[test]
+    fn test_word_list_formats() {
+        let directory =
+            std::env::temp_dir().join(format!("guavagrams-lists-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let text: &str = "cat\nTea\n\nat\n";
+        std::fs::write(directory.join("list.txt"), text).unwrap();
+        std::fs::write(directory.join("list.csv"), format!("word\n{text}")).unwrap();
+        // A gzip header with the original file name in it, then the trailer's checksum and size.
+        let mut gzipped: Vec<u8> = vec![0x1f, 0x8b, 8, 8, 0, 0, 0, 0, 0, 255];
+        gzipped.extend(b"list.txt\0");
+        gzipped.extend(miniz_oxide::deflate::compress_to_vec(text.as_bytes(), 6));
+        gzipped.extend(crc32(text.as_bytes()).to_le_bytes());
+        gzipped.extend(u32::try_from(text.len()).unwrap().to_le_bytes());
+        std::fs::write(directory.join("list.txt.gz"), &gzipped).unwrap();
+        std::fs::write(directory.join("broken.txt.gz"), &gzipped[..12]).unwrap();
+        // The same, but with a flipped bit in the checksum, and then in the size.
+        let mut bad_crc: Vec<u8> = gzipped.clone();
+        let crc: usize = bad_crc.len() - 8;
+        bad_crc[crc] ^= 1;
+        std::fs::write(directory.join("bad_crc.txt.gz"), &bad_crc).unwrap();
+        let mut bad_size: Vec<u8> = gzipped.clone();
+        let size: usize = bad_size.len() - 4;
+        bad_size[size] ^= 1;
+        std::fs::write(directory.join("bad_size.txt.gz"), &bad_size).unwrap();
+
+        for file in ["list.txt", "list.csv", "list.txt.gz"] {
+            let dictionary: Dictionary =
+                get_dictionary(&directory.join(file), Normalization::default()).unwrap();
+            assert_eq!(dictionary.name, "list");
+            assert_eq!(dictionary.words().count(), 3);
+            assert!(dictionary.contains("tea"));
+        }
+        for file in ["broken.txt.gz", "bad_crc.txt.gz", "bad_size.txt.gz"] {
+            assert!(get_dictionary(&directory.join(file), Normalization::default()).is_err());
+        }
+        // The check value from the CRC-32 spec.
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert!(get_dictionary(&directory.join("missing.txt"), Normalization::default()).is_err());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let mut dictionary: Dictionary = Dictionary::new(
+            ["cat", "cart", "care", "dog", "ñu"].map(String::from),
+            Normalization::default(),
+        );
+        assert!(dictionary.has_prefix("CA"));
+        assert!(dictionary.has_prefix("ñ"));
+        assert!(dictionary.has_prefix(""));
+        assert!(!dictionary.has_prefix("cab"));
+        assert_eq!(dictionary.completions("car"), ["care", "cart"]);
+        assert_eq!(dictionary.completions("c"), ["care", "cart", "cat"]);
+        assert!(dictionary.completions("x").is_empty());
+
+        // New words show up in lookups made after they're added.
+        dictionary.extend(["cab".to_string()]);
+        assert!(dictionary.has_prefix("cab"));
+    }
+
+    #[test]
+    fn test_merge_dictionaries() {
+        let directory = std::env::temp_dir();
+        let base = directory.join(format!("guavagrams-base-{}.csv", std::process::id()));
+        let extra = directory.join(format!("guavagrams-extra-{}.csv", std::process::id()));
+        std::fs::write(&base, "word\ncat\ntea\n").unwrap();
+        std::fs::write(&extra, "word\nYeet\ncat\n").unwrap();
+        let mut dictionary: Dictionary =
+            get_dictionaries(&[base.clone(), extra.clone()], Normalization::default()).unwrap();
+        std::fs::remove_file(&base).unwrap();
+        std::fs::remove_file(&extra).unwrap();
+
+        assert_eq!(dictionary.words().count(), 3);
+        assert!(dictionary.contains("tea"));
+        assert!(dictionary.contains("yeet"));
+        assert_eq!(
+            dictionary.name,
+            format!(
+                "guavagrams-base-{0} + guavagrams-extra-{0}",
+                std::process::id()
+            )
+        );
+
+        // Each word knows which file it came from.
+        let [base, extra] = [0, 1].map(|x: usize| dictionary.sources()[x].clone());
+        assert_eq!(dictionary.sources_of("Cat"), [&base, &extra]);
+        assert_eq!(dictionary.sources_of("tea"), [&base]);
+        dictionary.extend(["tea".to_string(), "zzz".to_string()]);
+        assert_eq!(dictionary.sources_of("tea"), [&base]);
+        assert!(dictionary.sources_of("zzz").is_empty());
+        assert_eq!(
+            dictionary.count_by_source(["cat", "yeet", "zzz"]),
+            [(base.as_str(), 1), (extra.as_str(), 2)]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Drills deal a single tricky rack with nothing left in the pile, so the only
+//! way to finish is to play every tile.
+
+use std::{fs, path::Path};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// A rack to practice with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drill {
+    pub name: String,
+    pub rack: Vec<char>,
+}
+
+/// Reads drills written as `<name>: <letters>`, one per line.
+///
+/// Blank lines and lines starting with `#` are skipped.
+pub fn parse_drills(contents: &str) -> Result<Vec<Drill>, Error> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line: &&str| !line.is_empty() && !line.starts_with('#'))
+        .map(|line: &str| {
+            let (name, letters) = line
+                .rsplit_once(':')
+                .ok_or_else(|| Error::DrillFailed(format!("\"{line}\" has no rack")))?;
+            let rack: Vec<char> = letters
+                .trim()
+                .chars()
+                .flat_map(char::to_lowercase)
+                .collect();
+            if rack.is_empty() || !rack.iter().all(|x: &char| x.is_alphabetic()) {
+                return Err(Error::DrillFailed(format!(
+                    "\"{}\" isn't a rack of letters",
+                    letters.trim()
+                )));
+            }
+            Ok(Drill {
+                name: name.trim().to_string(),
+                rack,
+            })
+        })
+        .collect()
+}
+
+/// Reads a drill file.
+pub fn load_drills(path: &Path) -> Result<Vec<Drill>, Error> {
+    let drills: Vec<Drill> = parse_drills(
+        &fs::read_to_string(path).map_err(|x: std::io::Error| Error::DrillFailed(x.to_string()))?,
+    )?;
+    if drills.is_empty() {
+        return Err(Error::DrillFailed(format!(
+            "{} has no drills in it",
+            path.display()
+        )));
+    }
+    Ok(drills)
+}
+
+/// Picks one of the drills, the same one every time for the same seed.
+pub fn pick(drills: &[Drill], seed: u64) -> Drill {
+    drills[ChaCha12Rng::seed_from_u64(seed).random_range(..drills.len())].clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Drill, load_drills, parse_drills, pick};
+
+    #[test]
+    fn test_parse_drills() {
+        let drills: Vec<Drill> = parse_drills("# Comment\n\nQ without U: QatNiqb\n").unwrap();
+        assert_eq!(
+            drills,
+            [Drill {
+                name: "Q without U".to_string(),
+                rack: "qatniqb".chars().collect(),
+            }]
+        );
+        assert!(parse_drills("No rack here").is_err());
+        assert!(parse_drills("Digits: abc1").is_err());
+        assert_eq!(parse_drills("Eñe: AÑO").unwrap()[0].rack, ['a', 'ñ', 'o']);
+
+        // The bundled drills should all load.
+        assert!(
+            !load_drills(Path::new("drills/tricky.txt"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_pick_is_seeded() {
+        let drills: Vec<Drill> = load_drills(Path::new("drills/tricky.txt")).unwrap();
+        for seed in 0..8 {
+            assert_eq!(pick(&drills, seed), pick(&drills, seed));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Everything the game can run into, on top of what the board, dictionary and scoring
+//! can.
+
+use guavagrams::grid::Coordinate;
+
+#[derive(Debug, thiserror::Error, Clone)]
+pub enum Error {
+    #[error(transparent)]
+    Core(#[from] guavagrams::Error),
+    #[error("The pile's all out of tiles, or there isn't enough to pull!")]
+    NoMoreTiles,
+    #[error("Trading in takes 3 tiles from the pile, but there's only {0} left!")]
+    PileTooSmall(usize),
+    #[error("You still have tiles in your hand!")]
+    HandHasTiles,
+    #[error("You don't have a '{0}' in your hand!")]
+    NotInHand(char),
+    #[error("Couldn't copy to the clipboard!")]
+    ClipboardUnavailable,
+    #[error("Couldn't export the game: {0}")]
+    ExportFailed(String),
+    #[error("Couldn't import the game: {0}")]
+    ImportFailed(String),
+    #[error(
+        "The replay ended on a different board than the game did! (checksum {1:016x}, not {0:016x})"
+    )]
+    ReplayDiverged(u64, u64),
+    #[error("Couldn't save the game: {0}")]
+    SaveFailed(String),
+    #[error("Couldn't resume the game: {0}")]
+    ResumeFailed(String),
+    #[error("\"{0}\" isn't a verbosity! Try silent, important or everything.")]
+    UnknownVerbosity(String),
+    #[error("Couldn't record the session: {0}")]
+    RecordingFailed(String),
+    #[error("Couldn't update the score cam: {0}")]
+    ScoreCamFailed(String),
+    #[error("Couldn't write the render profile: {0}")]
+    ProfileFailed(String),
+    #[error("Couldn't save the screenshot: {0}")]
+    ScreenshotFailed(String),
+    #[error("A starting hand of {0} tiles won't fit in a pile of {1}!")]
+    HandTooBig(usize, usize),
+    #[error("Couldn't load the config: {0}")]
+    ConfigFailed(String),
+    #[error("Couldn't update the high scores: {0}")]
+    ScoresFailed(String),
+    #[error("Couldn't load the drills: {0}")]
+    DrillFailed(String),
+    #[error("Couldn't use the house dictionary: {0}")]
+    HouseDictionaryFailed(String),
+    #[error("Couldn't update the word journal: {0}")]
+    JournalFailed(String),
+    #[error("Couldn't practice: {0}")]
+    PracticeFailed(String),
+}
+
+impl Error {
+    /// The tiles to blame, for errors about the board.
+    pub fn cells(&self) -> &[Coordinate] {
+        match self {
+            Self::Core(exception) => exception.cells(),
+            _ => &[],
+        }
+    }
+}
@@ -2,188 +2,360 @@ pub mod index;
 
 use std::collections::{HashMap, HashSet};
 
-pub use index::{Coordinate, GridIndex};
+pub use index::Coordinate;
+use serde::{Deserialize, Serialize};
 
-use crate::{Error, box_array};
+use crate::{Error, dictionary::Dictionary, rules::Rules, scoring::Scoring, util::mix};
 
-/// The amount of columns in a grid.
-/// The default is 256.
-const GRID_WIDTH: usize = 256;
-/// The amount of rows in the grid.
-/// The default is 256.
-const GRID_HEIGHT: usize = 256;
+/// The offsets of the four cells next to a cell.
+const NEIGHBOURS: [Coordinate; 4] = [
+    Coordinate(1, 0),
+    Coordinate(-1, 0),
+    Coordinate(0, 1),
+    Coordinate(0, -1),
+];
 
-/// A 2D, fixed size array on the heap.
-#[derive(Debug, Clone)]
-pub struct Grid<T>(Box<[[T; GRID_HEIGHT]; GRID_WIDTH]>);
+/// An effectively unbounded grid that only stores the cells that have been written to.
+///
+/// Every other cell reads as the filler, so a board takes as much memory as it
+/// has tiles, however far it sprawls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Grid<T> {
+    #[serde(with = "cells")]
+    cells: HashMap<Coordinate, T>,
+    filler: T,
+}
 
 impl<T> Grid<T>
 where
-    T: Copy,
+    T: Copy + PartialEq,
 {
     /// Constructs a `Grid`.
     pub fn new(filler: T) -> Self {
-        Self(box_array![[filler; GRID_HEIGHT]; GRID_WIDTH])
+        Self {
+            cells: HashMap::new(),
+            filler,
+        }
     }
-}
 
-#[allow(clippy::cast_possible_truncation)]
-impl Grid<Option<char>> {
-    /// Scans a `Grid` for words.
-    pub fn scan_for_words(&self) -> Vec<String> {
-        let mut output: Vec<String> = Vec::new();
-        let mut current_word: String = String::new();
-
-        // Scan horizontally.
-        for y in 0..GRID_WIDTH {
-            for x in 0..GRID_HEIGHT {
-                if let Some(letter) = self[GridIndex(x as u8, y as u8)] {
-                    let direction: Direction =
-                        self.letter_adjacent(GridIndex(x as u8, y as u8).into());
-                    if direction != Direction::Vertical {
-                        current_word.push(letter);
-                    }
-                } else if !current_word.is_empty() {
-                    output.push(current_word);
-                    current_word = String::new();
-                }
+    /// Iterates over every cell that isn't the filler, in no particular order.
+    pub fn occupied_cells(&self) -> impl Iterator<Item = (Coordinate, &T)> {
+        self.cells
+            .iter()
+            .filter(|(_, value): &(&Coordinate, &T)| **value != self.filler)
+            .map(|(coordinate, value): (&Coordinate, &T)| (*coordinate, value))
+    }
+
+    /// Finds the bottom-left and top-right corners of the smallest box containing every occupied cell.
+    pub fn bounding_box(&self) -> Option<(Coordinate, Coordinate)> {
+        self.occupied_cells()
+            .map(|(coordinate, _)| coordinate)
+            .fold(None, |bounds, coordinate: Coordinate| {
+                Some(bounds.map_or(
+                    (coordinate, coordinate),
+                    |(min, max): (Coordinate, Coordinate)| {
+                        (
+                            Coordinate(min.0.min(coordinate.0), min.1.min(coordinate.1)),
+                            Coordinate(max.0.max(coordinate.0), max.1.max(coordinate.1)),
+                        )
+                    },
+                ))
+            })
+    }
+
+    /// Iterates over the rows of a region, from the top down, each read left to right.
+    ///
+    /// `region` is a bottom-left and top-right corner, like `bounding_box` gives.
+    pub fn rows_in(
+        &self,
+        (min, max): (Coordinate, Coordinate),
+    ) -> impl Iterator<Item = impl Iterator<Item = (Coordinate, T)>> {
+        (min.1..=max.1).rev().map(move |y: i32| {
+            (min.0..=max.0).map(move |x: i32| (Coordinate(x, y), self[Coordinate(x, y)]))
+        })
+    }
+
+    /// Rewrites every cell in a region, keeping cells set back to the filler out of storage.
+    pub fn apply_region(
+        &mut self,
+        (min, max): (Coordinate, Coordinate),
+        mut change: impl FnMut(Coordinate, T) -> T,
+    ) {
+        for coordinate in
+            (min.1..=max.1).flat_map(|y: i32| (min.0..=max.0).map(move |x: i32| Coordinate(x, y)))
+        {
+            let value: T = change(coordinate, self[coordinate]);
+            if value == self.filler {
+                self.cells.remove(&coordinate);
+            } else {
+                self.cells.insert(coordinate, value);
+            }
+        }
+    }
+
+    /// Lists every cell that's different in `other`, in no particular order.
+    ///
+    /// Sending these instead of the whole board keeps syncing cheap, since a move
+    /// only ever changes a cell or two.
+    pub fn diff(&self, other: &Self) -> Vec<CellChange<T>> {
+        let coordinates: HashSet<Coordinate> = self
+            .occupied_cells()
+            .chain(other.occupied_cells())
+            .map(|(coordinate, _)| coordinate)
+            .collect();
+        coordinates
+            .into_iter()
+            .filter(|coordinate: &Coordinate| self[*coordinate] != other[*coordinate])
+            .map(|coordinate: Coordinate| CellChange {
+                coordinate,
+                old: self[coordinate],
+                new: other[coordinate],
+            })
+            .collect()
+    }
+
+    /// Makes the changes from a `diff`.
+    ///
+    /// If any cell isn't what the change expects it to be, the grids have drifted
+    /// apart, and nothing is changed.
+    ///
+    /// # Errors
+    ///
+    /// `Error::BoardDesynced` at the first cell that doesn't match.
+    pub fn apply_diff(&mut self, changes: &[CellChange<T>]) -> Result<(), Error> {
+        if let Some(change) = changes
+            .iter()
+            .find(|change: &&CellChange<T>| self[change.coordinate] != change.old)
+        {
+            return Err(Error::BoardDesynced(change.coordinate));
+        }
+        for change in changes {
+            if change.new == self.filler {
+                self.cells.remove(&change.coordinate);
+            } else {
+                self.cells.insert(change.coordinate, change.new);
             }
         }
+        Ok(())
+    }
 
-        if !current_word.is_empty() {
-            output.push(current_word);
-            current_word = String::new();
-        }
-
-        // Scan vertically.
-        for x in 0..GRID_HEIGHT {
-            for y in 0..GRID_WIDTH {
-                if let Some(letter) = self[GridIndex(x as u8, y as u8)] {
-                    let direction = self.letter_adjacent(GridIndex(x as u8, y as u8).into());
-                    if direction != Direction::Horizontal {
-                        current_word.push(letter);
-                    }
-                } else if current_word.chars().count() == 1 {
-                    current_word = String::new();
-                } else if !current_word.is_empty() {
-                    output.push(current_word);
-                    current_word = String::new();
+    /// Resets a cell to the filler, returning what was there.
+    pub fn remove(&mut self, coordinate: Coordinate) -> T {
+        self.cells.remove(&coordinate).unwrap_or(self.filler)
+    }
+
+    /// Lifts every occupied cell in a region and puts them all down again, shifted by `offset`.
+    ///
+    /// Cells can land where others in the region were, but not on anything outside it.
+    /// If one would, everything goes back where it was.
+    ///
+    /// # Errors
+    ///
+    /// `Error::TileInTheWay` at the first cell, going from left to right, that's already taken.
+    pub fn move_region(
+        &mut self,
+        (min, max): (Coordinate, Coordinate),
+        offset: Coordinate,
+    ) -> Result<usize, Error> {
+        let in_region =
+            |x: Coordinate| (min.0..=max.0).contains(&x.0) && (min.1..=max.1).contains(&x.1);
+        let mut lifted: Vec<(Coordinate, T)> = self
+            .occupied_cells()
+            .filter(|(coordinate, _)| in_region(*coordinate))
+            .map(|(coordinate, value)| (coordinate, *value))
+            .collect();
+        lifted.sort_by_key(|(coordinate, _)| (coordinate.0, coordinate.1));
+        for (coordinate, _) in &lifted {
+            self.cells.remove(coordinate);
+        }
+
+        for (index, (coordinate, value)) in lifted.iter().enumerate() {
+            let target: Coordinate = coordinate.overflowing_add(offset).0;
+            if self[target] != self.filler {
+                for (coordinate, _) in &lifted[..index] {
+                    self.cells.remove(&coordinate.overflowing_add(offset).0);
                 }
+                self.cells.extend(lifted.iter().copied());
+                return Err(Error::TileInTheWay(target));
             }
+            self.cells.insert(target, *value);
         }
+        Ok(lifted.len())
+    }
+}
+
+impl Grid<Option<char>> {
+    /// Scans a `Grid` for words, reading left to right and top to bottom.
+    ///
+    /// Any run of two or more tiles in a row or column is a word. A tile with no
+    /// neighbours at all is reported as a one letter (horizontal) word, so that
+    /// stray tiles still get checked against the dictionary.
+    ///
+    /// Only the tiles themselves are visited, never the empty cells around them, so
+    /// a scan costs the same however big the grid is or wherever the board sits on it.
+    #[must_use]
+    pub fn scan_for_words(&self) -> Vec<Word> {
+        let mut output: Vec<Word> = Vec::new();
+        let mut tiles: Vec<Coordinate> = self
+            .occupied_cells()
+            .map(|(coordinate, _)| coordinate)
+            .collect();
 
-        if !current_word.is_empty() {
-            output.push(current_word);
+        for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+            // Sort by line, then along it, so words come out in reading order.
+            tiles.sort_unstable_by_key(|coordinate: &Coordinate| match orientation {
+                Orientation::Horizontal => (-i64::from(coordinate.1), i64::from(coordinate.0)),
+                Orientation::Vertical => (i64::from(coordinate.0), -i64::from(coordinate.1)),
+            });
+
+            for start in &tiles {
+                // Only start reading at the first tile of a run.
+                if orientation
+                    .step(*start, false)
+                    .is_some_and(|coordinate: Coordinate| self[coordinate].is_some())
+                {
+                    continue;
+                }
+
+                let mut word: Word = Word {
+                    text: String::new(),
+                    start: *start,
+                    orientation,
+                };
+                let mut position: Option<Coordinate> = Some(*start);
+                while let Some(letter) = position.and_then(|x: Coordinate| self[x]) {
+                    word.text.push(letter);
+                    position = position.and_then(|x: Coordinate| orientation.step(x, true));
+                }
+
+                if word.text.chars().count() > 1
+                    || (orientation == Orientation::Horizontal && !self.has_neighbour(word.start))
+                {
+                    output.push(word);
+                }
+            }
         }
 
         output
     }
 
-    /// Checks if there's a letter adjacent to this coordinate.
-    /// Returns the direction the letter was found in.
-    fn letter_adjacent(&self, coordinate: Coordinate) -> Direction {
-        let horizontal: bool =
-            if let (x, false) = coordinate.overflowing_add(Coordinate(1, 0)) {
-                self[x].is_some()
-            } else {
-                false
-            } || if let (x, false) = coordinate.overflowing_add(Coordinate(-1, 0)) {
-                self[x].is_some()
-            } else {
-                false
-            };
-        let vertical: bool = if let (x, false) = coordinate.overflowing_add(Coordinate(0, 1)) {
-            self[x].is_some()
-        } else {
-            false
-        } || if let (x, false) = coordinate.overflowing_add(Coordinate(0, -1))
-        {
-            self[x].is_some()
-        } else {
-            false
-        };
+    /// Checks if any of the four cells around a coordinate has a tile on it.
+    fn has_neighbour(&self, coordinate: Coordinate) -> bool {
+        NEIGHBOURS.iter().any(|direction: &Coordinate| {
+            let (neighbour, overflowed) = coordinate.overflowing_add(*direction);
+            !overflowed && self[neighbour].is_some()
+        })
+    }
 
-        if horizontal && vertical {
-            return Direction::Both;
-        } else if horizontal {
-            return Direction::Horizontal;
-        } else if vertical {
-            return Direction::Vertical;
+    /// A fingerprint of every tile on the board, to check two copies of it still agree.
+    ///
+    /// It doesn't depend on the order tiles are stored in, so every machine gets
+    /// the same checksum for the same board.
+    #[must_use]
+    pub fn checksum(&self) -> u64 {
+        self.occupied_cells()
+            .filter_map(|(coordinate, tile)| Some((coordinate, (*tile)?)))
+            .fold(0, |sum: u64, (Coordinate(x, y), tile)| {
+                // Each part gets its own bits, then the cell is scrambled so sums don't collide.
+                let cell: u64 = (u64::from(x.cast_unsigned()) << 32 | u64::from(y.cast_unsigned()))
+                    ^ mix(u64::from(tile));
+                sum.wrapping_add(mix(cell))
+            })
+    }
+
+    /// Scans the board once and summarizes it.
+    #[must_use]
+    pub fn stats(&self) -> BoardStats {
+        let tiles: usize = self.occupied_cells().count();
+        let dimensions: (u64, u64) = self.bounding_box().map_or((0, 0), |(min, max)| {
+            (
+                (i64::from(max.0) - i64::from(min.0)).unsigned_abs() + 1,
+                (i64::from(max.1) - i64::from(min.1)).unsigned_abs() + 1,
+            )
+        });
+        BoardStats {
+            words: self.scan_for_words(),
+            tiles,
+            dimensions,
+            checksum: self.checksum(),
         }
-        Direction::None
     }
 
     /// Checks every word to ensure it is in the dictionary.
-    pub fn validate_words(words: &[String], dictionary: &HashSet<String>) -> Result<(), Error> {
-        for word in words.iter().cloned() {
-            if !dictionary.contains(&word) {
-                return Err(Error::InvalidWord(word));
-            }
-        }
-        Ok(())
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidWord` names the first bad word, but carries the tiles of every bad word.
+    pub fn validate_words(words: &[Word], dictionary: &Dictionary) -> Result<(), Error> {
+        let mut invalid = words
+            .iter()
+            .filter(|word: &&Word| !dictionary.contains(&word.text))
+            .peekable();
+        let Some(first) = invalid.peek().map(|word: &&Word| word.text.clone()) else {
+            return Ok(());
+        };
+        Err(Error::InvalidWord(
+            first,
+            invalid.flat_map(Word::cells).collect(),
+        ))
     }
 
-    /// Depth-first search to traverse all connected cells.
-    fn dfs(&self, visited: &mut Grid<bool>, coordinate: Coordinate) {
-        /// Every direction that DFS can go in.
-        const DIRECTIONS: [Coordinate; 4] = [
-            Coordinate(1, 0),
-            Coordinate(-1, 0),
-            Coordinate(0, 1),
-            Coordinate(0, -1),
-        ];
-
-        visited[coordinate] = true;
-        for direction in DIRECTIONS {
-            let (new_coord, overflowed) = coordinate.overflowing_add(direction);
-            if overflowed {
-                continue;
-            }
-            if self[new_coord].is_some() && !visited[new_coord] {
-                self.dfs(visited, new_coord);
+    /// Collects every tile connected to `start`, marking them as visited.
+    ///
+    /// This keeps its own stack rather than recursing, so a long snake of tiles
+    /// can't run the program out of stack.
+    fn connected_group(&self, visited: &mut Grid<bool>, start: Coordinate) -> Vec<Coordinate> {
+        let mut group: Vec<Coordinate> = Vec::new();
+        let mut stack: Vec<Coordinate> = vec![start];
+        visited[start] = true;
+        while let Some(coordinate) = stack.pop() {
+            group.push(coordinate);
+            for direction in NEIGHBOURS {
+                let (neighbour, overflowed) = coordinate.overflowing_add(direction);
+                if !overflowed && self[neighbour].is_some() && !visited[neighbour] {
+                    visited[neighbour] = true;
+                    stack.push(neighbour);
+                }
             }
         }
+        group
     }
 
-    /// Ensures that all words are connected.
-    pub fn validate_connectivity(&self) -> Result<(), Error> {
+    /// Splits the tiles up into groups that touch each other.
+    #[must_use]
+    pub fn connected_groups(&self) -> Vec<Vec<Coordinate>> {
         let mut visited: Grid<bool> = Grid::default();
+        let mut groups: Vec<Vec<Coordinate>> = Vec::new();
 
-        // Find the first occupied cell to start DFS.
-        let mut start: Option<GridIndex> = None;
-        for x in 0..GRID_HEIGHT {
-            for y in 0..GRID_WIDTH {
-                if self[GridIndex(x as u8, y as u8)].is_some() {
-                    start = Some(GridIndex(x as u8, y as u8));
-                    break;
-                }
-            }
-            if start.is_some() {
-                break;
+        for (start, _) in self.occupied_cells() {
+            if !visited[start] {
+                groups.push(self.connected_group(&mut visited, start));
             }
         }
+        groups
+    }
 
-        // Perform DFS from the first occupied cell.
-        if let Some(index) = start {
-            self.dfs(&mut visited, index.into());
-
-            // Check if all occupied cells are visited.
-            for y in 0..GRID_HEIGHT {
-                for x in 0..GRID_WIDTH {
-                    if self[GridIndex(x as u8, y as u8)].is_some()
-                        && !visited[GridIndex(x as u8, y as u8)]
-                    {
-                        // Found an unconnected cell!
-                        return Err(Error::WordsNotConnected);
-                    }
-                }
-            }
+    /// Ensures that all words are connected.
+    ///
+    /// # Errors
+    ///
+    /// The biggest group of tiles counts as the board, and `Error::WordsNotConnected`
+    /// carries every tile cut off from it.
+    pub fn validate_connectivity(&self) -> Result<(), Error> {
+        let mut groups: Vec<Vec<Coordinate>> = self.connected_groups();
+        if groups.len() > 1 {
+            let biggest: usize = (0..groups.len())
+                .max_by_key(|x: &usize| groups[*x].len())
+                .unwrap_or_default();
+            groups.swap_remove(biggest);
+            return Err(Error::WordsNotConnected(groups.concat()));
         }
         Ok(())
     }
 
-    #[allow(clippy::cast_precision_loss)]
-    pub fn score_grid(words: &[String], scoretable: &HashMap<char, i64>) -> i64 {
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    #[must_use]
+    pub fn score_grid(words: &[Word], scoring: &Scoring, rules: &Rules) -> i64 {
         /*
             Stale (previously used) words: 0.8x
             Length of word: 1-3 is 1x, 4-6 is 1.5x, 7-9 is 2x, 10+ is 2.5x
@@ -192,16 +364,17 @@ impl Grid<Option<char>> {
         let mut seen = HashSet::new();
         let stale = words
             .iter()
+            .map(|word: &Word| &word.text)
             .filter(|s| !seen.insert(*s)) // Keep only the first instance of each string
             .collect::<Vec<_>>();
         let mut change: i64 = 0;
 
-        for word in words {
+        for word in words.iter().map(|word: &Word| &word.text) {
             let mut word_score: f64 = 0.0;
 
             // Score letters
             for tile in word.chars() {
-                word_score += *scoretable.get(&tile).unwrap_or(&0) as f64;
+                word_score += scoring.points(tile) as f64;
             }
 
             // Length multiplier
@@ -212,6 +385,8 @@ impl Grid<Option<char>> {
                 _ => 2.5,
             };
 
+            word_score = rules.score_word(word, word_score);
+
             // Stale word check
             // rescoring every word is a feature, not a bug. trust me. - clover <3
             for _ in 0..stale.iter().filter(|x: &&&String| **x == word).count() {
@@ -227,17 +402,413 @@ impl Grid<Option<char>> {
 
 impl<T> Default for Grid<T>
 where
-    T: Default + Copy,
+    T: Default + Copy + PartialEq,
 {
     fn default() -> Self {
         Self::new(T::default())
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Direction {
-    None,
-    Vertical,
+/// A cell that's different between two grids, and what it changed from and to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellChange<T> {
+    pub coordinate: Coordinate,
+    pub old: T,
+    pub new: T,
+}
+
+/// A summary of a board, cached so that it isn't rescanned every frame.
+#[derive(Debug, Clone, Default)]
+pub struct BoardStats {
+    /// Every word currently on the board.
+    pub words: Vec<Word>,
+    /// The amount of tiles on the board.
+    pub tiles: usize,
+    /// The width and height of the board's bounding box.
+    pub dimensions: (u64, u64),
+    /// The board's checksum, to catch two copies of a game drifting apart.
+    pub checksum: u64,
+}
+
+/// Whether a board would pass a peel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoardHealth {
+    /// Every word is valid and connected.
+    #[default]
+    Valid,
+    /// Every word is valid, but they aren't all connected.
+    Disconnected,
+    /// Some words aren't in the dictionary.
+    InvalidWords,
+}
+
+/// Which way a word reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Left to right.
     Horizontal,
-    Both,
+    /// Top to bottom.
+    Vertical,
+}
+
+impl Orientation {
+    /// Moves one cell along the reading direction, or back against it.
+    /// Returns `None` at the edge of the grid.
+    #[must_use]
+    pub fn step(self, coordinate: Coordinate, forwards: bool) -> Option<Coordinate> {
+        let direction: i32 = if forwards { 1 } else { -1 };
+        match self {
+            Self::Horizontal => Some(Coordinate(
+                coordinate.0.checked_add(direction)?,
+                coordinate.1,
+            )),
+            // Up is positive, so reading down goes down in Y.
+            Self::Vertical => Some(Coordinate(
+                coordinate.0,
+                coordinate.1.checked_sub(direction)?,
+            )),
+        }
+    }
+}
+
+/// A word on the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub text: String,
+    /// Where the word's first letter is.
+    pub start: Coordinate,
+    pub orientation: Orientation,
+}
+
+impl Word {
+    /// Where each of the word's letters is, from first to last.
+    pub fn cells(&self) -> impl Iterator<Item = Coordinate> {
+        let orientation: Orientation = self.orientation;
+        std::iter::successors(Some(self.start), move |x: &Coordinate| {
+            orientation.step(*x, true)
+        })
+        .take(self.text.chars().count())
+    }
+
+    /// Where `query` first shows up in the word, if it does.
+    #[must_use]
+    pub fn find(&self, query: &str) -> Option<Coordinate> {
+        let offset: usize = self.text[..self.text.find(query)?].chars().count();
+        (0..offset).try_fold(self.start, |coordinate: Coordinate, _| {
+            self.orientation.step(coordinate, true)
+        })
+    }
+}
+
+/// Saves cells as a list of pairs, since formats like JSON only allow strings as map keys.
+mod cells {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Coordinate;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        cells: &HashMap<Coordinate, T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(cells)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Coordinate, T>, D::Error> {
+        Ok(Vec::<(Coordinate, T)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CellChange, Coordinate, Grid, Orientation, Word};
+    use crate::{
+        Error,
+        dictionary::{Dictionary, Normalization},
+    };
+
+    /// Builds a board from rows of text, with the first letter at `origin`.
+    /// Spaces are empty cells.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn board(origin: Coordinate, rows: &[&str]) -> Grid<Option<char>> {
+        let mut grid: Grid<Option<char>> = Grid::default();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, letter) in row.chars().enumerate() {
+                if letter != ' ' {
+                    grid[origin + Coordinate(x as i32, -(y as i32))] = Some(letter);
+                }
+            }
+        }
+        grid
+    }
+
+    fn word(text: &str, start: Coordinate, orientation: Orientation) -> Word {
+        Word {
+            text: text.to_string(),
+            start,
+            orientation,
+        }
+    }
+
+    #[test]
+    fn test_find_in_word() {
+        let across: Word = word("crate", Coordinate(2, 3), Orientation::Horizontal);
+        assert_eq!(across.find("at"), Some(Coordinate(4, 3)));
+        assert_eq!(across.find("cat"), None);
+        // Reading down goes down the board.
+        let down: Word = word("crate", Coordinate(2, 3), Orientation::Vertical);
+        assert_eq!(down.find("te"), Some(Coordinate(2, 0)));
+    }
+
+    #[test]
+    fn test_empty_board() {
+        assert!(Grid::default().scan_for_words().is_empty());
+    }
+
+    #[test]
+    fn test_single_words() {
+        assert_eq!(
+            board(Coordinate(0, 0), &["cat"]).scan_for_words(),
+            vec![word("cat", Coordinate(0, 0), Orientation::Horizontal)]
+        );
+        assert_eq!(
+            board(Coordinate(0, 0), &["c", "a", "t"]).scan_for_words(),
+            vec![word("cat", Coordinate(0, 0), Orientation::Vertical)]
+        );
+    }
+
+    #[test]
+    fn test_single_letters() {
+        // A lone tile is still checked.
+        assert_eq!(
+            board(Coordinate(5, 5), &["a"]).scan_for_words(),
+            vec![word("a", Coordinate(5, 5), Orientation::Horizontal)]
+        );
+        // But letters that are only part of a word going the other way aren't.
+        assert_eq!(
+            board(Coordinate(0, 0), &["c", "a", "t"])
+                .scan_for_words()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_crossings() {
+        let words: Vec<Word> = board(Coordinate(0, 0), &["cat", "a", "r t"]).scan_for_words();
+        assert_eq!(
+            words,
+            vec![
+                word("cat", Coordinate(0, 0), Orientation::Horizontal),
+                word("t", Coordinate(2, -2), Orientation::Horizontal),
+                word("car", Coordinate(0, 0), Orientation::Vertical),
+            ]
+        );
+
+        let words: Vec<Word> = board(Coordinate(-1, 1), &[" b ", "bob", " b "]).scan_for_words();
+        assert_eq!(
+            words,
+            vec![
+                word("bob", Coordinate(-1, 0), Orientation::Horizontal),
+                word("bob", Coordinate(0, 1), Orientation::Vertical),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edges() {
+        // Words touching the far edges of the grid end there, instead of wrapping around.
+        let mut grid: Grid<Option<char>> = Grid::default();
+        grid[Coordinate(i32::MAX - 1, 10)] = Some('a');
+        grid[Coordinate(i32::MAX, 10)] = Some('n');
+        grid[Coordinate(i32::MIN, 9)] = Some('d');
+        grid[Coordinate(i32::MIN + 1, 9)] = Some('o');
+        assert_eq!(
+            grid.scan_for_words(),
+            vec![
+                word("an", Coordinate(i32::MAX - 1, 10), Orientation::Horizontal),
+                word("do", Coordinate(i32::MIN, 9), Orientation::Horizontal),
+            ]
+        );
+
+        let mut grid: Grid<Option<char>> = Grid::default();
+        grid[Coordinate(10, i32::MIN + 1)] = Some('a');
+        grid[Coordinate(10, i32::MIN)] = Some('n');
+        grid[Coordinate(11, i32::MAX)] = Some('d');
+        grid[Coordinate(11, i32::MAX - 1)] = Some('o');
+        assert_eq!(
+            grid.scan_for_words(),
+            vec![
+                word("an", Coordinate(10, i32::MIN + 1), Orientation::Vertical),
+                word("do", Coordinate(11, i32::MAX), Orientation::Vertical),
+            ]
+        );
+
+        // Nothing gets clipped past where the board used to end.
+        let grid: Grid<Option<char>> = board(Coordinate(126, -126), &["snake"]);
+        assert_eq!(
+            grid.scan_for_words(),
+            vec![word(
+                "snake",
+                Coordinate(126, -126),
+                Orientation::Horizontal
+            )]
+        );
+        assert_eq!(grid.stats().dimensions, (5, 1));
+    }
+
+    #[test]
+    fn test_sparse_storage() {
+        let mut grid: Grid<Option<char>> = board(Coordinate(-128, 127), &["ab"]);
+        assert_eq!(grid.cells.len(), 2);
+        assert_eq!(grid[Coordinate(0, 0)], None);
+
+        // Tiles far apart don't make a word.
+        grid[Coordinate(127, -128)] = Some('c');
+        assert_eq!(grid.scan_for_words().len(), 2);
+
+        // Picking tiles up frees their cells.
+        assert_eq!(grid.remove(Coordinate(-128, 127)), Some('a'));
+        assert_eq!(grid.remove(Coordinate(-128, 127)), None);
+        grid[Coordinate(127, -128)] = None;
+        assert_eq!(grid.occupied_cells().count(), 1);
+        assert_eq!(grid.cells.len(), 2);
+    }
+
+    #[test]
+    fn test_diffs() {
+        let before: Grid<Option<char>> = board(Coordinate(0, 0), &["cat"]);
+        let after: Grid<Option<char>> = board(Coordinate(0, 0), &["ca", "  o"]);
+
+        let mut changes: Vec<CellChange<Option<char>>> = before.diff(&after);
+        changes.sort_by_key(|change| change.coordinate);
+        assert_eq!(
+            changes,
+            [
+                CellChange {
+                    coordinate: Coordinate(2, -1),
+                    old: None,
+                    new: Some('o'),
+                },
+                CellChange {
+                    coordinate: Coordinate(2, 0),
+                    old: Some('t'),
+                    new: None,
+                },
+            ]
+        );
+
+        let mut synced: Grid<Option<char>> = before.clone();
+        synced.apply_diff(&changes).unwrap();
+        assert_eq!(synced.checksum(), after.checksum());
+        assert_ne!(before.checksum(), after.checksum());
+        assert!(synced.diff(&after).is_empty());
+
+        // Applying the same changes twice means the copies have drifted apart.
+        assert!(synced.apply_diff(&changes).is_err());
+        assert_eq!(synced.checksum(), after.checksum());
+    }
+
+    #[test]
+    fn test_move_region() {
+        let mut grid: Grid<Option<char>> = board(Coordinate(0, 0), &["cat", " x "]);
+        let before: Grid<Option<char>> = grid.clone();
+
+        // The 'a' would land on the 't', which isn't being moved, so nothing moves.
+        let region: (Coordinate, Coordinate) = (Coordinate(0, 0), Coordinate(1, 0));
+        assert!(matches!(
+            grid.move_region(region, Coordinate(1, 0)),
+            Err(Error::TileInTheWay(Coordinate(2, 0)))
+        ));
+        assert!(grid.diff(&before).is_empty());
+
+        // Tiles can land where others being moved just were.
+        let moved: usize = grid
+            .move_region((Coordinate(0, 0), Coordinate(2, 0)), Coordinate(1, 0))
+            .unwrap();
+        assert_eq!(moved, 3);
+        assert_eq!(grid[Coordinate(0, 0)], None);
+        assert_eq!(grid[Coordinate(1, 0)], Some('c'));
+        assert_eq!(grid[Coordinate(3, 0)], Some('t'));
+        assert_eq!(grid[Coordinate(1, -1)], Some('x'));
+        assert_eq!(grid.move_region(region, Coordinate(0, 5)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_regions() {
+        // c a t
+        // . . o
+        let mut grid: Grid<Option<char>> = board(Coordinate(0, 0), &["cat", "  o"]);
+        let region: (Coordinate, Coordinate) = grid.bounding_box().unwrap();
+        assert_eq!(region, (Coordinate(0, -1), Coordinate(2, 0)));
+
+        let rows: Vec<String> = grid
+            .rows_in(region)
+            .map(|row| row.map(|(_, tile)| tile.unwrap_or('.')).collect())
+            .collect();
+        assert_eq!(rows, ["cat", "..o"]);
+
+        // Clearing a region frees its cells, even ones that were only ever read.
+        grid.apply_region((Coordinate(0, 0), Coordinate(5, 0)), |_, _| None);
+        assert_eq!(grid.occupied_cells().count(), 1);
+        assert_eq!(grid.cells.len(), 1);
+        grid.apply_region(region, |coordinate, tile| {
+            tile.or((coordinate.1 == 0).then_some('x'))
+        });
+        assert_eq!(grid.occupied_cells().count(), 4);
+    }
+
+    #[test]
+    fn test_offending_cells() {
+        let dictionary: Dictionary =
+            Dictionary::new(["cat", "at"].map(String::from), Normalization::default());
+
+        // c a t . x
+        // . . o . .
+        let grid: Grid<Option<char>> = board(Coordinate(0, 0), &["cat x", "  o"]);
+        let Err(exception) = grid.validate_connectivity() else {
+            panic!("the x isn't connected");
+        };
+        assert_eq!(exception.cells(), [Coordinate(4, 0)]);
+
+        // Every bad word is blamed, not just the first one named.
+        let Err(exception) = Grid::validate_words(&grid.scan_for_words(), &dictionary) else {
+            panic!("\"x\" and \"to\" aren't words");
+        };
+        assert!(matches!(&exception, Error::InvalidWord(word, _) if word == "x"));
+        let mut cells: Vec<Coordinate> = exception.cells().to_vec();
+        cells.sort_unstable_by_key(|Coordinate(x, y)| (*x, *y));
+        assert_eq!(
+            cells,
+            [Coordinate(2, -1), Coordinate(2, 0), Coordinate(4, 0)]
+        );
+    }
+
+    #[test]
+    fn test_long_chains() {
+        // A snake winding across the whole grid, tens of thousands of tiles long.
+        let mut grid: Grid<Option<char>> = Grid::default();
+        for y in (-128..128).step_by(2) {
+            for x in -128..128 {
+                grid[Coordinate(x, y)] = Some('a');
+            }
+            let turn: i32 = if y % 4 == 0 { 127 } else { -128 };
+            if y < 126 {
+                grid[Coordinate(turn, y + 1)] = Some('a');
+            }
+        }
+        assert!(grid.validate_connectivity().is_ok());
+
+        // Cutting it in two leaves the smaller end to blame.
+        grid[Coordinate(127, 125)] = None;
+        let Err(exception) = grid.validate_connectivity() else {
+            panic!("the snake is cut");
+        };
+        assert_eq!(exception.cells().len(), 256);
+    }
 }
@@ -0,0 +1,247 @@
+//! Undoing and redoing moves.
+//!
+//! Every move is already recorded in `GameState::actions`, and replaying them
+//! from the seed deals out exactly the same game, random draws and all. So
+//! undoing a move is just replaying every move before it, and no move has to
+//! know how to reverse itself.
+
+use std::time::Duration;
+
+use ratatui::style::{Color, Style, Styled};
+
+use crate::{
+    Error, EventResponse, GameState,
+    action::{self, Action},
+    bot::Bot,
+};
+
+/// Moves that have been undone, most recent last.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undone: Vec<Action>,
+}
+
+impl History {
+    /// Forgets every undone move, since making a new one starts a different future.
+    pub fn clear(&mut self) {
+        self.undone.clear();
+    }
+}
+
+/// Takes back the last move, unless it can't be taken back.
+pub fn undo(state: &mut GameState) -> EventResponse {
+    let Some(&action) = state.actions.last() else {
+        return EventResponse::ChangeStatus(
+            "There's nothing to undo.".set_style(Style::new().fg(Color::Yellow)),
+        );
+    };
+    if is_final(action) {
+        return EventResponse::ChangeStatus(
+            format!("Can't undo past {}.", describe(action))
+                .set_style(Style::new().fg(Color::Yellow)),
+        );
+    }
+    state.actions.pop();
+    // Replaying makes moves, which would forget the undone ones, so keep them out of the way.
+    let mut undone: Vec<Action> = std::mem::take(&mut state.history.undone);
+    replay(state);
+    undone.push(action);
+    state.history.undone = undone;
+    EventResponse::ChangeStatus(
+        format!("Undid {}.", describe(action)).set_style(Style::new().fg(Color::Cyan)),
+    )
+}
+
+/// Makes the last undone move again.
+pub fn redo(state: &mut GameState) -> Result<EventResponse, Error> {
+    let Some(action) = state.history.undone.pop() else {
+        return Ok(EventResponse::ChangeStatus(
+            "There's nothing to redo.".set_style(Style::new().fg(Color::Yellow)),
+        ));
+    };
+    // Keep the rest of the undone moves out of the way, so redoing isn't mistaken for a new move.
+    let undone: Vec<Action> = std::mem::take(&mut state.history.undone);
+    let response: Result<EventResponse, Error> = action::apply(state, action);
+    state.history.undone = undone;
+    response
+}
+
+/// Deals the game again and makes every recorded move, leaving out anything undone.
+fn replay(state: &mut GameState) {
+    let actions: Vec<Action> = std::mem::take(&mut state.actions);
+    // Replayed moves would be timed now, so keep the times they were first made.
+    let times: Vec<Duration> = std::mem::take(&mut state.action_times);
+    // The bot's words aren't recorded, so dealing again would wipe its board. Its
+    // peels are still replayed, since they take tiles from the pile the player draws from.
+    let bot: Option<Bot> = state.bot.clone();
+    state.deal_once();
+    state.camera.clear();
+    state.score = 0;
+    state.last_delta = None;
+    // Failed peels are part of the record, so keep going past them.
+    for action in actions {
+        let _ = action::apply(state, action);
+    }
+    state.action_times = times;
+    state.action_times.truncate(state.actions.len());
+    state.bot = bot;
+}
+
+/// Whether a move stays made. A hint can't be unseen, and neither can the tiles a
+/// trade-in drew, peels and the buzzer have already dealt or scored, and the bot's
+/// peel is the bot's move to take back.
+const fn is_final(action: Action) -> bool {
+    matches!(
+        action,
+        Action::Hint | Action::Dump(..) | Action::Peel | Action::Buzzer | Action::BotPeel
+    )
+}
+
+/// A short description of a move, to follow "Undid".
+fn describe(action: Action) -> String {
+    match action {
+        Action::Place(letter, coordinate) => format!("placing '{letter}' at {coordinate}"),
+        Action::PickUp(coordinate) => format!("picking up from {coordinate}"),
+        Action::PickUpRun(coordinate, ..) => format!("picking up a run from {coordinate}"),
+        Action::Move(min, max, ..) => format!("moving the tiles from {min} to {max}"),
+        Action::Dump(letter) => format!("trading in '{letter}'"),
+        Action::Peel => "a peel".to_string(),
+        Action::Buzzer => "the buzzer".to_string(),
+        Action::Hint => "a hint".to_string(),
+        Action::BotPeel => "the bot's peel".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{redo, undo};
+    use crate::{
+        GameState,
+        action::{self, Action},
+        bot::{Bot, Turn},
+        config::Config,
+        dictionary::{Dictionary, Normalization},
+        grid::Coordinate,
+        tiles::Hand,
+    };
+
+    #[test]
+    fn test_undo_redo() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 3);
+        state.start();
+        let hand: Vec<char> = state.hand.tiles().to_vec();
+        let (first, second): (char, char) = (hand[0], hand[1]);
+        action::apply(&mut state, Action::Place(first, Coordinate(0, 0))).unwrap();
+        action::apply(&mut state, Action::Place(second, Coordinate(1, 0))).unwrap();
+
+        undo(&mut state);
+        assert_eq!(state.camera.grid[Coordinate(1, 0)], None);
+        assert_eq!(state.camera.grid[Coordinate(0, 0)], Some(first));
+        assert_eq!(state.hand.len(), hand.len() - 1);
+
+        redo(&mut state).unwrap();
+        assert_eq!(state.camera.grid[Coordinate(1, 0)], Some(second));
+        assert_eq!(state.actions.len(), 2);
+
+        // Undoing all the way back and forth again ends up where it started.
+        undo(&mut state);
+        undo(&mut state);
+        assert_eq!(state.hand.tiles(), hand);
+        redo(&mut state).unwrap();
+        redo(&mut state).unwrap();
+        assert_eq!(state.camera.grid[Coordinate(0, 0)], Some(first));
+        assert_eq!(state.camera.grid[Coordinate(1, 0)], Some(second));
+
+        // A new move means there's nothing left to redo.
+        undo(&mut state);
+        action::apply(&mut state, Action::PickUp(Coordinate(0, 0))).unwrap();
+        redo(&mut state).unwrap();
+        assert_eq!(state.camera.grid[Coordinate(1, 0)], None);
+        assert_eq!(state.hand.len(), hand.len());
+    }
+
+    #[test]
+    fn test_undo_run() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 3);
+        state.start();
+        let hand: Vec<char> = state.hand.tiles().to_vec();
+        for (x, tile) in (0..3).zip(&hand) {
+            action::apply(&mut state, Action::Place(*tile, Coordinate(x, 0))).unwrap();
+        }
+
+        // The whole run comes up in one move, and goes back down in one undo.
+        action::apply(
+            &mut state,
+            Action::PickUpRun(Coordinate(1, 0), Coordinate(1, 0)),
+        )
+        .unwrap();
+        assert_eq!(state.camera.grid.occupied_cells().count(), 1);
+        assert_eq!(state.hand.len(), hand.len() - 1);
+        undo(&mut state);
+        assert_eq!(state.camera.grid.occupied_cells().count(), 3);
+
+        // Starting on an empty cell picks up nothing.
+        action::apply(
+            &mut state,
+            Action::PickUpRun(Coordinate(5, 0), Coordinate(-1, 0)),
+        )
+        .unwrap();
+        assert_eq!(state.actions.len(), 3);
+    }
+
+    #[test]
+    fn test_undo_keeps_the_bot() {
+        let dictionary: Dictionary =
+            Dictionary::new(["cat"].map(String::from), Normalization::default());
+        let mut state: GameState = GameState::new(dictionary, &Config::default(), 3);
+        state.bot = Some(Bot::new(Duration::from_secs(5)));
+        state.players = 2;
+        state.deal();
+        state.start();
+        let bot: &mut Bot = state.bot.as_mut().unwrap();
+        bot.hand = Hand::default();
+        for tile in "cat".chars() {
+            bot.hand.draw(tile);
+        }
+        let turn: Turn = bot.play(&state.dictionary, &state.scoring, &state.rules);
+        assert!(matches!(turn, Turn::Played(word) if word.text == "cat"));
+
+        let tile: char = state.hand.tiles()[0];
+        action::apply(&mut state, Action::Place(tile, Coordinate(0, 0))).unwrap();
+        undo(&mut state);
+        assert_eq!(state.camera.grid[Coordinate(0, 0)], None);
+        let bot: &Bot = state.bot.as_ref().unwrap();
+        assert_eq!(bot.grid.occupied_cells().count(), 3);
+        assert!(bot.hand.is_empty());
+    }
+
+    #[test]
+    fn test_undo_stops_at_final_moves() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 3);
+        let hand: Vec<char> = state.hand.tiles().to_vec();
+        // Something for the hint to find.
+        let word: String = hand[..3].iter().collect();
+        state.dictionary = Dictionary::new([word], Normalization::default());
+        state.start();
+        action::apply(&mut state, Action::Hint).unwrap();
+        let score: i64 = state.score;
+        action::apply(&mut state, Action::Place(hand[3], Coordinate(0, 0))).unwrap();
+
+        // Moves after the hint can be taken back, but the hint and its cost stay.
+        undo(&mut state);
+        undo(&mut state);
+        assert_eq!(state.actions, [Action::Hint]);
+        assert_eq!(state.score, score);
+        assert_eq!(state.camera.grid[Coordinate(0, 0)], None);
+
+        // A trade-in stays made too, along with its cost and the tiles it drew.
+        let letter: char = state.hand.tiles()[0];
+        action::apply(&mut state, Action::Dump(letter)).unwrap();
+        let hand: Vec<char> = state.hand.tiles().to_vec();
+        undo(&mut state);
+        assert_eq!(state.actions, [Action::Hint, Action::Dump(letter)]);
+        assert_eq!(state.hand.tiles(), hand);
+    }
+}
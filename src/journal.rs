@@ -0,0 +1,283 @@
+//! Every word played, across every game, for looking back over.
+//!
+//! Words go in when a game finishes with a board that would pass a peel, so the
+//! journal only ever holds words that were really played.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use itertools::Itertools;
+use ratatui::{
+    DefaultTerminal, Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, dictionary::Dictionary, grid::Word};
+
+/// Where the journal is kept, relative to the working directory.
+pub const JOURNAL_PATH: &str = "saves/journal.json";
+
+/// How often a word has been played, and when.
+///
+/// Dates are days since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub count: u64,
+    pub first: u64,
+    pub last: u64,
+    /// The dictionaries it was played from, when more than one was in play.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub sources: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    words: BTreeMap<String, Entry>,
+}
+
+impl Journal {
+    /// Reads the journal kept at `path`. It's fine for there not to be one yet.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let fail = |exception: String| Error::JournalFailed(exception);
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|x| fail(x.to_string())),
+            Err(exception) if exception.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(exception) => Err(fail(exception.to_string())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let fail = |exception: String| Error::JournalFailed(exception);
+        let json: String = serde_json::to_string(self).map_err(|x| fail(x.to_string()))?;
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, json))
+            .map_err(|x| fail(x.to_string()))
+    }
+
+    /// Counts each word once for every time it shows up.
+    pub fn add<'a>(&mut self, words: impl IntoIterator<Item = &'a str>, day: u64) {
+        for word in words {
+            self.words
+                .entry(word.to_string())
+                .and_modify(|entry: &mut Entry| {
+                    entry.count += 1;
+                    entry.last = day;
+                })
+                .or_insert(Entry {
+                    count: 1,
+                    first: day,
+                    last: day,
+                    sources: BTreeSet::new(),
+                });
+        }
+    }
+
+    /// Notes which dictionaries a word was played from.
+    pub fn tag<'a>(&mut self, word: &str, sources: impl IntoIterator<Item = &'a str>) {
+        if let Some(entry) = self.words.get_mut(word) {
+            entry
+                .sources
+                .extend(sources.into_iter().map(str::to_string));
+        }
+    }
+
+    /// Every word with `query` in it, most played first.
+    pub fn search(&self, query: &str) -> Vec<(&str, &Entry)> {
+        let mut found: Vec<(&str, &Entry)> = self
+            .words
+            .iter()
+            .filter(|(word, ..)| word.contains(query))
+            .map(|(word, entry)| (word.as_str(), entry))
+            .collect();
+        // The map is already alphabetical, and the sort keeps that for ties.
+        found.sort_by_key(|(.., entry)| std::cmp::Reverse(entry.count));
+        found
+    }
+}
+
+/// Puts the words of a finished board in the journal kept at `path`, noting which
+/// dictionaries each came from if more than one was in play.
+pub fn record(words: &[Word], dictionary: &Dictionary, path: &Path) -> Result<(), Error> {
+    let mut journal: Journal = Journal::load(path)?;
+    journal.add(words.iter().map(|word: &Word| word.text.as_str()), today());
+    if dictionary.sources().len() > 1 {
+        for word in words {
+            journal.tag(&word.text, dictionary.sources_of(&word.text));
+        }
+    }
+    journal.save(path)
+}
+
+/// How many days it's been since the Unix epoch.
+fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400
+}
+
+/// Writes a day since the Unix epoch as a date, like 2025-03-14.
+fn format_day(day: u64) -> String {
+    // Counts in 400-year eras from 0000-03-01, so leap days fall at the end of each year.
+    let days: u64 = day + 719_468;
+    let era: u64 = days / 146_097;
+    let day_of_era: u64 = days % 146_097;
+    let year_of_era: u64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: u64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index: u64 = (5 * day_of_year + 2) / 153;
+    let day_of_month: u64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month: u64 = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year: u64 = year_of_era + era * 400 + u64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day_of_month:02}")
+}
+
+/// What the journal screen is showing.
+struct JournalView {
+    journal: Journal,
+    /// The search being typed, if one is.
+    typing: Option<String>,
+    /// The last search made.
+    query: String,
+    /// How far down the list has been scrolled.
+    scroll: usize,
+}
+
+/// Shows the journal, with scrolling and searching, until the player quits.
+pub fn run(terminal: &mut DefaultTerminal, journal: Journal) -> io::Result<()> {
+    let mut view: JournalView = JournalView {
+        journal,
+        typing: None,
+        query: String::new(),
+        scroll: 0,
+    };
+
+    loop {
+        terminal.draw(|frame: &mut Frame| draw(frame, &view))?;
+        let Event::Key(event) = event::read()? else {
+            continue;
+        };
+        if event.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if let Some(typing) = &mut view.typing {
+            match event.code {
+                KeyCode::Char(letter) => typing.push(letter),
+                KeyCode::Backspace => {
+                    typing.pop();
+                }
+                KeyCode::Enter => {
+                    view.query = typing.to_lowercase();
+                    view.typing = None;
+                    view.scroll = 0;
+                }
+                KeyCode::Esc => view.typing = None,
+                _ => (),
+            }
+            continue;
+        }
+        match event.code {
+            KeyCode::Down => {
+                let found: usize = view.journal.search(&view.query).len();
+                view.scroll = (view.scroll + 1).min(found.saturating_sub(1));
+            }
+            KeyCode::Up => view.scroll = view.scroll.saturating_sub(1),
+            KeyCode::Char('/') => view.typing = Some(String::new()),
+            KeyCode::Esc | KeyCode::Char('Q') => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, view: &JournalView) {
+    let [list, footer]: [Rect; 2] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+
+    let found: Vec<(&str, &Entry)> = view.journal.search(&view.query);
+    let title: String = if view.query.is_empty() {
+        format!("Word Journal ({} words)", found.len())
+    } else {
+        format!(
+            "Word Journal ({} words with \"{}\")",
+            found.len(),
+            view.query
+        )
+    };
+    let mut lines: Vec<Line> = vec![Line::styled(
+        format!(
+            "{:<20} {:>6}  {:<10}  {:<10}  {}",
+            "Word", "Played", "First", "Last", "From"
+        ),
+        Style::new().add_modifier(Modifier::BOLD),
+    )];
+    lines.extend(found.iter().skip(view.scroll).map(|(word, entry)| {
+        Line::raw(format!(
+            "{word:<20} {:>6}  {}  {}  {}",
+            entry.count,
+            format_day(entry.first),
+            format_day(entry.last),
+            entry.sources.iter().join(", ")
+        ))
+    }));
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        list,
+    );
+
+    let line: Line = match &view.typing {
+        Some(typing) => Line::raw(format!("Search: {typing}_")),
+        None => Line::styled(
+            " ↑/↓ Scroll  / Search  Shift + Q/Esc Quit",
+            Style::new().fg(Color::DarkGray),
+        ),
+    };
+    frame.render_widget(line, footer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Journal, format_day};
+
+    #[test]
+    fn test_journal() {
+        let mut journal: Journal = Journal::default();
+        journal.add(["cat", "at", "cat"], 10);
+        journal.add(["at", "tea"], 12);
+
+        let found: Vec<&str> = journal.search("").iter().map(|(word, ..)| *word).collect();
+        assert_eq!(found, ["at", "cat", "tea"]);
+        let (.., at) = journal.search("a")[0];
+        assert_eq!((at.count, at.first, at.last), (2, 10, 12));
+        assert_eq!(journal.search("te").len(), 1);
+
+        journal.tag("tea", ["english"]);
+        journal.tag("tea", ["spanish"]);
+        journal.tag("missing", ["english"]);
+        let (.., tea) = journal.search("tea")[0];
+        assert_eq!(
+            tea.sources.iter().collect::<Vec<_>>(),
+            ["english", "spanish"]
+        );
+        assert_eq!(journal.search("").len(), 3);
+
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(11_029), "2000-03-13");
+        assert_eq!(format_day(19_782), "2024-02-29");
+    }
+}
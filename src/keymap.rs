@@ -0,0 +1,280 @@
+//! Cleans up key presses before the game sees them.
+//!
+//! This is where input options that don't belong to any one control live:
+//! sticky modifiers, for players who can't hold a chord, debouncing, for
+//! players whose presses repeat when they don't mean them to, and double taps,
+//! for players who want a shortcut without another key to remember.
+//!
+//! A key with a double tap is held back for the tap window, so the first tap of
+//! a pair never does what the key does on its own. It goes through once the
+//! window runs out or another key is pressed.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, ModifierKeyCode};
+use serde::Deserialize;
+
+/// Something to do when a key is pressed twice in quick succession.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoubleTap {
+    /// Picks up the word under the cursor, in one move.
+    ClearWord,
+    /// Checks the board like a peel would, without peeling.
+    Check,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Lets a modifier be pressed on its own, then applied to the next key.
+    ///
+    /// Terminals only report lone modifier presses once keyboard enhancement
+    /// is turned on, which `main` does when this is set.
+    pub sticky_modifiers: bool,
+    /// How long after a key is accepted that the same key is ignored.
+    pub debounce: Duration,
+    /// How soon a key has to be pressed again to count as a double tap.
+    pub tap_window: Duration,
+    /// What double tapping each key does.
+    pub double_taps: HashMap<KeyCode, DoubleTap>,
+    /// The double tap the last key accepted finished, if it finished one.
+    tapped: Option<DoubleTap>,
+    /// A key with a double tap, held back in case it's the first of a pair, and when it was pressed.
+    held: Option<(KeyEvent, Instant)>,
+    /// Modifiers pressed on their own, waiting for the next key.
+    latched: KeyModifiers,
+    /// The last key accepted, and when.
+    last: Option<(KeyCode, KeyModifiers, Instant)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            sticky_modifiers: false,
+            debounce: Duration::ZERO,
+            tap_window: Duration::ZERO,
+            double_taps: HashMap::new(),
+            tapped: None,
+            held: None,
+            latched: KeyModifiers::NONE,
+            last: None,
+        }
+    }
+}
+
+impl Keymap {
+    /// Modifiers waiting to be applied to the next key.
+    pub const fn latched(&self) -> KeyModifiers {
+        self.latched
+    }
+
+    /// What to do instead of the last key accepted, if it finished a double tap.
+    pub const fn tapped(&self) -> Option<DoubleTap> {
+        self.tapped
+    }
+
+    /// Lets go of a held key once the tap window has run out without a second tap.
+    pub fn release(&mut self, now: Instant) -> Option<KeyEvent> {
+        let (event, at) = self.held?;
+        if now.duration_since(at) < self.tap_window {
+            return None;
+        }
+        self.held = None;
+        self.tapped = None;
+        Some(event)
+    }
+
+    /// How long until a held key is let go of, if one is held.
+    pub fn until_release(&self, now: Instant) -> Option<Duration> {
+        self.held
+            .map(|(_, at)| (at + self.tap_window).saturating_duration_since(now))
+    }
+
+    /// Turns a key press into the keys the game should act on, in order.
+    ///
+    /// That's usually just the key, but it can be nothing if the key was held back, or a
+    /// held key followed by this one.
+    pub fn translate(&mut self, mut event: KeyEvent, now: Instant) -> Vec<KeyEvent> {
+        if let KeyCode::Modifier(modifier) = event.code {
+            if self.sticky_modifiers
+                && let Some(modifier) = latchable(modifier)
+            {
+                self.latched.toggle(modifier);
+            }
+            return Vec::new();
+        }
+
+        if self.sticky_modifiers {
+            event.modifiers |= std::mem::replace(&mut self.latched, KeyModifiers::NONE);
+        }
+        // Enhanced keyboards report Shift+G as a lowercase 'g' with Shift held.
+        if event.modifiers.contains(KeyModifiers::SHIFT)
+            && let KeyCode::Char(letter) = event.code
+        {
+            let mut upper = letter.to_uppercase();
+            if let (Some(upper), None) = (upper.next(), upper.next()) {
+                event.code = KeyCode::Char(upper);
+            }
+        }
+
+        if let Some((code, modifiers, at)) = self.last
+            && code == event.code
+            && modifiers == event.modifiers
+            && now.duration_since(at) < self.debounce
+        {
+            return Vec::new();
+        }
+        self.last = Some((event.code, event.modifiers, now));
+
+        self.tapped = None;
+        let mut keys: Vec<KeyEvent> = Vec::new();
+        if let Some((held, at)) = self.held.take() {
+            if held == event && now.duration_since(at) < self.tap_window {
+                self.tapped = self.double_taps.get(&event.code).copied();
+                return vec![event];
+            }
+            keys.push(held);
+        }
+        // A third tap starts a new pair, rather than finishing another one.
+        if self.double_taps.contains_key(&event.code) && !self.tap_window.is_zero() {
+            self.held = Some((event, now));
+        } else {
+            keys.push(event);
+        }
+        keys
+    }
+}
+
+/// Reads a key's name from a config, like `backspace`, `enter` or `G`.
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut letters = name.chars();
+    match (letters.next(), letters.next()) {
+        (Some(letter), None) => return Some(KeyCode::Char(letter)),
+        (None, _) => return None,
+        _ => (),
+    }
+    match name.to_lowercase().as_str() {
+        "backspace" => Some(KeyCode::Backspace),
+        "delete" => Some(KeyCode::Delete),
+        "enter" => Some(KeyCode::Enter),
+        "tab" => Some(KeyCode::Tab),
+        "space" => Some(KeyCode::Char(' ')),
+        _ => None,
+    }
+}
+
+/// The modifier flag a modifier key sets, for the modifiers that can be latched.
+const fn latchable(modifier: ModifierKeyCode) -> Option<KeyModifiers> {
+    match modifier {
+        ModifierKeyCode::LeftShift | ModifierKeyCode::RightShift => Some(KeyModifiers::SHIFT),
+        ModifierKeyCode::LeftControl | ModifierKeyCode::RightControl => Some(KeyModifiers::CONTROL),
+        ModifierKeyCode::LeftAlt | ModifierKeyCode::RightAlt => Some(KeyModifiers::ALT),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, ModifierKeyCode};
+
+    use super::{DoubleTap, Keymap, parse_key};
+
+    #[test]
+    fn test_sticky_modifiers() {
+        let now: Instant = Instant::now();
+        let mut keymap: Keymap = Keymap {
+            sticky_modifiers: true,
+            ..Keymap::default()
+        };
+
+        let control: KeyEvent = KeyEvent::from(KeyCode::Modifier(ModifierKeyCode::LeftControl));
+        assert!(keymap.translate(control, now).is_empty());
+        assert_eq!(keymap.latched(), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.translate(KeyEvent::from(KeyCode::Char('q')), now),
+            [KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL)]
+        );
+        // The latch only lasts one key.
+        assert_eq!(keymap.latched(), KeyModifiers::NONE);
+
+        let shift: KeyEvent = KeyEvent::from(KeyCode::Modifier(ModifierKeyCode::RightShift));
+        keymap.translate(shift, now);
+        assert_eq!(
+            keymap.translate(KeyEvent::from(KeyCode::Char('g')), now),
+            [KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)]
+        );
+
+        // Pressing a modifier twice lets go of it.
+        keymap.translate(control, now);
+        keymap.translate(control, now);
+        assert_eq!(keymap.latched(), KeyModifiers::NONE);
+    }
+
+    #[test]
+    fn test_debounce() {
+        let now: Instant = Instant::now();
+        let mut keymap: Keymap = Keymap {
+            debounce: Duration::from_millis(100),
+            ..Keymap::default()
+        };
+        let a: KeyEvent = KeyEvent::from(KeyCode::Char('a'));
+        let b: KeyEvent = KeyEvent::from(KeyCode::Char('b'));
+
+        assert_eq!(keymap.translate(a, now), [a]);
+        assert!(
+            keymap
+                .translate(a, now + Duration::from_millis(50))
+                .is_empty()
+        );
+        assert_eq!(keymap.translate(b, now + Duration::from_millis(60)), [b]);
+        assert_eq!(keymap.translate(b, now + Duration::from_millis(200)), [b]);
+    }
+
+    #[test]
+    fn test_double_tap() {
+        let now: Instant = Instant::now();
+        let mut keymap: Keymap = Keymap {
+            tap_window: Duration::from_millis(300),
+            double_taps: [(KeyCode::Backspace, DoubleTap::ClearWord)].into(),
+            ..Keymap::default()
+        };
+        let backspace: KeyEvent = KeyEvent::from(KeyCode::Backspace);
+        let enter: KeyEvent = KeyEvent::from(KeyCode::Enter);
+        let at = |millis: u64| now + Duration::from_millis(millis);
+
+        // The first tap is held back, and the second finishes the pair.
+        assert!(keymap.translate(backspace, at(0)).is_empty());
+        assert_eq!(
+            keymap.until_release(at(100)),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(keymap.translate(backspace, at(200)), [backspace]);
+        assert_eq!(keymap.tapped(), Some(DoubleTap::ClearWord));
+        // The third tap is the first of the next pair, and goes through once it's too late
+        // for a fourth.
+        assert!(keymap.translate(backspace, at(300)).is_empty());
+        assert_eq!(keymap.tapped(), None);
+        assert_eq!(keymap.release(at(500)), None);
+        assert_eq!(keymap.release(at(700)), Some(backspace));
+        assert_eq!(keymap.tapped(), None);
+        assert_eq!(keymap.until_release(at(700)), None);
+
+        // Another key lets the held one through first.
+        keymap.translate(backspace, at(1000));
+        assert_eq!(keymap.translate(enter, at(1100)), [backspace, enter]);
+        assert_eq!(keymap.tapped(), None);
+
+        // Keys without a double tap don't get one, or wait.
+        assert_eq!(keymap.translate(enter, at(1200)), [enter]);
+        assert_eq!(keymap.tapped(), None);
+
+        assert_eq!(parse_key("Backspace"), Some(KeyCode::Backspace));
+        assert_eq!(parse_key("G"), Some(KeyCode::Char('G')));
+        assert_eq!(parse_key("hyper"), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! A full account of why a board would or wouldn't pass a peel.
+//!
+//! A failed peel only names the first thing wrong. This goes through everything
+//! a peel checks, word by word, and also makes sure every tile dealt is still
+//! somewhere, for tracking down rejections that don't make sense.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::{
+    GameState, action,
+    dictionary::{BLANK, is_played_blank},
+    grid::{Coordinate, Grid, Orientation, Word},
+};
+
+/// Writes out everything a peel would check on the board, and whether the tiles add up.
+pub fn report(state: &GameState) -> String {
+    let mut output: String = String::new();
+    let words: &[Word] = &state.camera.stats.words;
+
+    writeln!(&mut output, "Words ({}):", words.len()).unwrap();
+    for word in words {
+        let verdict: String =
+            match Grid::validate_words(std::slice::from_ref(word), &state.dictionary)
+                .and_then(|()| state.rules.validate_words(std::slice::from_ref(word)))
+            {
+                Ok(()) => "ok".to_string(),
+                Err(exception) => exception.to_string(),
+            };
+        writeln!(
+            &mut output,
+            "  {:<12} {} {}: {verdict}",
+            word.text,
+            word.start,
+            match word.orientation {
+                Orientation::Horizontal => "across",
+                Orientation::Vertical => "down",
+            }
+        )
+        .unwrap();
+    }
+
+    let groups: Vec<Vec<Coordinate>> = state.camera.grid.connected_groups();
+    writeln!(
+        &mut output,
+        "\nConnectivity: {} group{} of tiles{}",
+        groups.len(),
+        if groups.len() == 1 { "" } else { "s" },
+        if groups.len() > 1 {
+            " (they all have to be joined up)"
+        } else {
+            ""
+        }
+    )
+    .unwrap();
+
+    writeln!(&mut output, "\nTiles: {}", tile_count(state)).unwrap();
+    writeln!(
+        &mut output,
+        "\nPeel: {}",
+        match action::validate_board(state) {
+            Ok(()) => "would pass".to_string(),
+            Err(exception) => format!("would fail. {exception}"),
+        }
+    )
+    .unwrap();
+    output
+}
+
+/// Checks every tile dealt is on a board, in a hand, or in the pile.
+fn tile_count(state: &GameState) -> String {
+    let on_board: Vec<char> = tiles_on(&state.camera.grid);
+    let mut counted: Vec<char> =
+        [on_board.as_slice(), state.hand.tiles(), state.pile.tiles()].concat();
+    if let Some(bot) = &state.bot {
+        counted.extend(tiles_on(&bot.grid));
+        counted.extend_from_slice(bot.hand.tiles());
+    }
+    let summary: String = format!(
+        "{} on the board, {} in the hand, {} in the pile",
+        on_board.len(),
+        state.hand.len(),
+        state.pile.len()
+    );
+    if state.endless {
+        return format!("{summary}. Endless games draw from a pile that isn't counted.");
+    }
+
+    // Dealing again from the seed gives back every tile the game started with.
+    let dealt: Vec<char> = match &state.drill {
+        Some(drill) => drill.rack.clone(),
+        None => state.distribution.create_pile(
+            state.pile_size,
+            &mut ChaCha12Rng::seed_from_u64(state.seed),
+            &state.rules,
+        ),
+    };
+    let mut difference: BTreeMap<char, isize> = BTreeMap::new();
+    for tile in counted {
+        *difference.entry(tile).or_default() += 1;
+    }
+    for tile in &dealt {
+        *difference.entry(*tile).or_default() -= 1;
+    }
+    let out: Vec<String> = difference
+        .iter()
+        .filter(|(.., count)| **count != 0)
+        .map(|(tile, count)| format!("{count:+} '{tile}'"))
+        .collect();
+    if out.is_empty() {
+        format!("{summary}. All {} dealt are accounted for.", dealt.len())
+    } else {
+        format!(
+            "{summary}. {} were dealt, but the count is off: {}",
+            dealt.len(),
+            out.join(", ")
+        )
+    }
+}
+
+/// The tiles on a board, with played blanks counted as blanks.
+fn tiles_on(grid: &Grid<Option<char>>) -> Vec<char> {
+    grid.occupied_cells()
+        .filter_map(|(.., tile)| *tile)
+        .map(|tile: char| if is_played_blank(tile) { BLANK } else { tile })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::report;
+    use crate::{
+        GameState,
+        action::{self, Action},
+        config::Config,
+        dictionary::{Dictionary, Normalization},
+        grid::Coordinate,
+    };
+
+    #[test]
+    fn test_report() {
+        let dictionary: Dictionary =
+            Dictionary::new(["zz"].map(String::from), Normalization::default());
+        let mut state: GameState = GameState::new(dictionary, &Config::default(), 5);
+        state.start();
+        let hand: Vec<char> = state.hand.tiles().to_vec();
+        for (coordinate, letter) in [Coordinate(0, 0), Coordinate(1, 0), Coordinate(5, 5)]
+            .into_iter()
+            .zip(hand)
+        {
+            action::apply(&mut state, Action::Place(letter, coordinate)).unwrap();
+        }
+
+        let report: String = report(&state);
+        // A lone tile counts as a word of its own.
+        assert!(report.starts_with("Words (2):\n"));
+        assert!(report.contains("(0, 0) across: Invalid word"));
+        assert!(report.contains("Connectivity: 2 groups of tiles"));
+        assert!(report.contains("3 on the board, 18 in the hand, 123 in the pile. All 144 dealt"));
+        assert!(report.contains("Peel: would fail."));
+    }
+}
@@ -0,0 +1,50 @@
+//! The board, dictionary and scoring behind Guavagrams, without the terminal.
+//!
+//! Everything needed to check and score a board lives here: `grid` holds the
+//! board and finds its words, `dictionary` says which words count and deals
+//! tiles, `rules` and `scoring` decide what a word is worth, and `solver` finds
+//! a word to play. None of it draws anything or reads input, so another
+//! frontend can use it as is. The terminal game is the binary built alongside.
+
+pub mod dictionary;
+pub mod grid;
+pub mod rules;
+pub mod scoring;
+pub mod solver;
+pub mod util;
+
+use grid::Coordinate;
+
+/// What can go wrong with a board, a dictionary or a table of scores.
+#[derive(Debug, thiserror::Error, Clone)]
+pub enum Error {
+    #[error("Not all words are connected!")]
+    WordsNotConnected(Vec<Coordinate>),
+    #[error("The board is out of sync at {0}!")]
+    BoardDesynced(Coordinate),
+    #[error("There's already a tile at {0}!")]
+    TileInTheWay(Coordinate),
+    #[error("Invalid word \"{0}\"!")]
+    InvalidWord(String, Vec<Coordinate>),
+    #[error("\"{0}\" is too short! Words need at least {1} letters.")]
+    WordTooShort(String, usize, Vec<Coordinate>),
+    #[error("\"{0}\" isn't a distribution! Try bananagrams or scrabble.")]
+    UnknownDistribution(String),
+    #[error("Couldn't load the dictionary: {0}")]
+    DictionaryFailed(String),
+    #[error("Couldn't load the letter scores: {0}")]
+    ScoringFailed(String),
+}
+
+impl Error {
+    /// The tiles to blame, for errors about the board.
+    #[must_use]
+    pub fn cells(&self) -> &[Coordinate] {
+        match self {
+            Self::WordsNotConnected(cells)
+            | Self::InvalidWord(.., cells)
+            | Self::WordTooShort(.., cells) => cells,
+            _ => &[],
+        }
+    }
+}
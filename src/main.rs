@@ -1,127 +1,1032 @@
+mod action;
+mod analysis;
+mod bot;
 mod camera;
-mod dictionary;
-mod grid;
+mod challenge;
+mod cli;
+mod config;
+mod dict_stats;
+mod drill;
+mod error;
+mod history;
+mod journal;
+mod keymap;
+mod legality;
+mod notation;
+mod practice;
+mod profile;
+mod recording;
+mod replay;
+mod save;
+mod score_cam;
+mod scores;
+mod screenshot;
+mod tiles;
 mod ui;
-mod util;
+mod viewer;
 
 use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
+    collections::VecDeque,
+    fs::File,
+    io::{BufWriter, stdout},
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    str::FromStr,
     time::{Duration, Instant},
 };
 
+use action::{Action, HintBudget};
+use analysis::{Difficulty, DifficultyRating, StyleScore};
+use bot::{Bot, Turn};
 use camera::Camera;
+use challenge::Challenge;
+use clap::Parser;
+use cli::{Cli, Command, DictCommand, Mode};
 use color_eyre::Result;
-use crossterm::event;
-use dictionary::{Distribution, get_dictionary, list_dictionaries};
+use config::{Config, ThemeWatcher};
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::supports_keyboard_enhancement,
+};
+use dictionary::{
+    Dictionary, Distribution, HOUSE_PATH, Normalization, get_dictionaries, get_dictionary,
+    list_dictionaries,
+};
+use drill::Drill;
+use error::Error;
 use grid::Grid;
-use ratatui::{prelude::*, style::Styled};
-use ui::{draw, event_handler};
+use guavagrams::{dictionary, grid, rules, scoring, solver, util};
+use history::History;
+use journal::{JOURNAL_PATH, Journal};
+use keymap::Keymap;
+use notation::GameRecord;
+use practice::{Attempts, STRUGGLES_PATH, Struggles};
+use profile::{RenderCosts, RenderProfile};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use ratatui::{DefaultTerminal, prelude::*, style::Styled};
+use recording::Recorder;
+use replay::Autoplay;
+use rules::Rules;
+use save::{SAVE_DIRECTORY, Save};
+use score_cam::ScoreCam;
+use scores::{SCORES_PATH, Standing};
+use scoring::Scoring;
+use tiles::{Hand, Pile};
+use ui::{Timer, draw, event_handler, key_controls, pick_dictionary};
+use util::mix;
 
+/// Everything about a game in progress.
+///
+/// The state has a single owner: the main loop, on the main thread. There's
+/// no shared grid or locking. Rendering only ever borrows the state, and every
+/// change to the board, hand, pile or score goes through `action::apply`, so
+/// anything that wants to change the game (the keyboard, an imported game, the
+/// bot, or later a network peer) does it by handing the main loop an `Action`.
 #[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
 struct GameState {
-    dictionary: HashSet<String>,
+    dictionary: Dictionary,
     camera: Camera,
     distribution: Distribution,
-    tileset: (Vec<char>, Vec<char>),
+    rules: Rules,
+    /// The weekly challenge being played, if this game is one.
+    challenge: Option<Challenge>,
+    /// The drill being practiced, if this game is one.
+    drill: Option<Drill>,
+    /// How the drill has gone before, counting this game once it's finished.
+    drill_attempts: Attempts,
+    /// How many tiles were asked for when the pile was made.
+    pile_size: usize,
+    /// How many tiles a starting hand gets.
+    hand_size: usize,
+    pile: Pile,
+    hand: Hand,
+    /// The hand as it was dealt, before any moves.
+    starting_hand: Vec<char>,
+    /// How many people are playing, which decides when the endgame starts.
+    players: usize,
+    /// The computer opponent, if this game is against one.
+    bot: Option<Bot>,
+    /// How hard the starting hand looks.
+    difficulty: Option<Difficulty>,
+    /// Whether hard starting hands are swapped out for easier ones.
+    casual: bool,
+    /// Whether tiles are drawn from a pile that never runs out.
+    endless: bool,
+    /// How long a timed game lasts, if this game is one.
+    time_limit: Option<Duration>,
+    /// How the clock is written, and when it starts warning.
+    timer: Timer,
+    /// The save slot the game goes in, if it isn't the one named after its seed.
+    save_slot: Option<String>,
+    /// The score that ends the game, if this game is played to one.
+    target_score: Option<i64>,
+    /// How many hints the game gets, and what each costs.
+    hints: HintBudget,
+    /// Whether a letter picked from the seed scores triple.
+    hot_letters: bool,
+    /// How long to wait before peeling once the hand is emptied, if auto-peel is on.
+    auto_peel: Option<Duration>,
+    /// When the auto-peel that's counting down goes off.
+    pending_peel: Option<Instant>,
+    /// Where the game placed on the high score table, once it's finished.
+    standing: Option<Standing>,
+    phase: GamePhase,
     game_start: Instant,
     game_end: Option<Instant>,
     score: i64,
-    scoretable: HashMap<char, i64>,
+    /// How nice the finished board looks, worked out when the game ends.
+    style: Option<StyleScore>,
+    /// The last change to the score and when it happened.
+    last_delta: Option<(i64, Instant)>,
+    scoring: Scoring,
     status: Span<'static>,
+    /// How much gets announced in the status line.
+    verbosity: Verbosity,
+    /// The seed every random choice in this game is made from.
+    seed: u64,
+    /// The same generator as `StdRng`, which can't be saved.
+    rng: ChaCha12Rng,
+    /// Which page of the hand is being shown.
+    hand_page: usize,
+    /// The order the hand's letters are shown in, or none for alphabetical.
+    hand_order: Vec<char>,
+    /// Where each letter in the hand was drawn in the last frame, for the mouse.
+    hand_spots: Vec<(Rect, char)>,
+    /// Whether the hand is split into vowels and consonants, with dead weight picked out.
+    group_hand: bool,
+    /// Letters in the hand that no word could use, kept up to date while `group_hand` is on.
+    dead_weight: Vec<char>,
+    /// The last word a check turned down, which can be added to the house dictionary.
+    rejected_word: Option<String>,
+    /// Whether the next Shift + H adds `rejected_word` to the house dictionary.
+    confirming_house_word: bool,
+    /// The letter whose draw chance is shown alongside the vowel chance.
+    watched_letter: Option<char>,
+    /// Every action taken this game, in order.
+    actions: Vec<Action>,
+    /// How far into the game each action was taken.
+    action_times: Vec<Duration>,
+    /// Actions that have been undone and can be redone.
+    history: History,
+    /// Imported actions that haven't been stepped through yet.
+    playback: VecDeque<Action>,
+    /// When each imported action comes up, if the replay plays itself.
+    playback_times: VecDeque<Duration>,
+    /// The checksum the imported game's board should end up with.
+    playback_checksum: Option<u64>,
+    /// How the imported game is playing itself, if it is.
+    autoplay: Autoplay,
+    /// How key presses are cleaned up before they're acted on.
+    keymap: Keymap,
+    /// Whether the next frame drawn should be saved as a screenshot.
+    screenshot_requested: bool,
+    /// When the player last pressed anything.
+    last_input: Instant,
+    /// How long the game can go without input before it pauses itself, or zero to never.
+    idle_timeout: Duration,
+    /// When the game was paused, if it is.
+    paused_since: Option<Instant>,
+    /// Which option is picked on the pause screen.
+    pause_choice: PauseChoice,
+    /// How long the game has spent paused, not counting the current pause.
+    paused_for: Duration,
+    /// Whether the debug overlay is shown.
+    debug_overlay: bool,
+    /// The board's legality report, while it's shown.
+    legality_report: Option<String>,
+    /// How the main loop is keeping up.
+    metrics: Metrics,
+    /// Whether to go easy on the terminal, for slow connections.
+    low_resource: bool,
+    /// Whether to keep things on screen from animating.
+    reduced_motion: bool,
+}
+
+/// How the main loop is keeping up, shown on the debug overlay.
+#[derive(Debug, Clone, Copy, Default)]
+struct Metrics {
+    /// How many events were waiting the last time input was handled.
+    input_depth: usize,
+    /// The most events that have ever been waiting at once.
+    input_peak: usize,
+    /// How long the last batch of events took to handle.
+    input_time: Duration,
+    /// How long the last frame took to draw.
+    frame_time: Duration,
+    /// How long each part of the last frame took to draw.
+    render: RenderCosts,
+    /// How many frames in a row have been slow to draw.
+    slow_frames: usize,
+}
+
+impl GameState {
+    /// Deals a new game from a seed, set up the way the config says.
+    fn new(dictionary: Dictionary, config: &Config, seed: u64) -> Self {
+        let mut camera: Camera = Camera::new(Grid::default());
+        camera.theme = config.theme;
+        camera.assist = config.assist;
+        let mut state: Self = Self {
+            dictionary,
+            camera,
+            distribution: config.distribution.clone(),
+            rules: Rules::default(),
+            challenge: None,
+            drill: None,
+            drill_attempts: Attempts::default(),
+            pile_size: config.pile_size(),
+            hand_size: config.hand_size,
+            pile: Pile::default(),
+            hand: Hand::default(),
+            starting_hand: Vec::new(),
+            players: 1,
+            bot: None,
+            difficulty: None,
+            casual: false,
+            endless: false,
+            time_limit: None,
+            timer: config.timer,
+            target_score: None,
+            save_slot: None,
+            hints: config.hints,
+            hot_letters: config.hot_letter,
+            auto_peel: config
+                .auto_peel
+                .then_some(Duration::from_millis(config.auto_peel_grace_ms)),
+            pending_peel: None,
+            standing: None,
+            phase: GamePhase::Setup,
+            game_start: Instant::now(),
+            game_end: None,
+            score: 0,
+            style: None,
+            last_delta: None,
+            scoring: config.scoring(),
+            status: "".set_style(Style::new().fg(Color::Black).bg(Color::White)),
+            verbosity: Verbosity::default(),
+            seed,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            hand_page: 0,
+            hand_order: Vec::new(),
+            hand_spots: Vec::new(),
+            group_hand: config.group_hand,
+            dead_weight: Vec::new(),
+            rejected_word: None,
+            confirming_house_word: false,
+            watched_letter: None,
+            actions: Vec::new(),
+            action_times: Vec::new(),
+            history: History::default(),
+            playback: VecDeque::new(),
+            playback_times: VecDeque::new(),
+            playback_checksum: None,
+            autoplay: Autoplay::default(),
+            keymap: Keymap::default(),
+            screenshot_requested: false,
+            last_input: Instant::now(),
+            idle_timeout: Duration::from_mins(config.idle_minutes),
+            paused_since: None,
+            pause_choice: PauseChoice::default(),
+            paused_for: Duration::ZERO,
+            debug_overlay: false,
+            legality_report: None,
+            metrics: Metrics::default(),
+            low_resource: false,
+            reduced_motion: config.reduced_motion,
+        };
+        state.set_low_resource(config.low_resource);
+        state.keymap.tap_window = Duration::from_millis(config.double_tap_ms);
+        state.keymap.double_taps = config
+            .double_tap
+            .iter()
+            .filter_map(|(name, tap)| Some((keymap::parse_key(name)?, *tap)))
+            .collect();
+        state.deal();
+        state
+    }
+
+    /// How many times casual games try for an easier starting hand.
+    const CASUAL_REDEALS: usize = 10;
+
+    /// Deals a game and rates the starting hand.
+    ///
+    /// In casual games, hard hands are re-dealt from a new seed a few times over.
+    fn deal(&mut self) {
+        for _ in 0..Self::CASUAL_REDEALS {
+            self.deal_once();
+            if !self.casual
+                || self.drill.is_some()
+                || self
+                    .difficulty
+                    .is_none_or(|x: Difficulty| x.rating() < DifficultyRating::Hard)
+            {
+                return;
+            }
+            self.seed = self.rng.random();
+        }
+        self.deal_once();
+    }
+
+    /// Deals the pile and a starting hand from the seed, under the current rules.
+    ///
+    /// A drill deals its whole rack instead, leaving the pile empty.
+    fn deal_once(&mut self) {
+        self.rng = ChaCha12Rng::seed_from_u64(self.seed);
+        self.pick_hot_letter();
+        if let Some(drill) = &self.drill {
+            self.pile = Pile::new(drill.rack.clone());
+            self.hand = Hand::default();
+            self.pile
+                .deal(&mut self.hand, drill.rack.len(), &mut self.rng)
+                .unwrap();
+            self.hand.forget_new_tiles();
+            self.starting_hand = self.hand.tiles().to_vec();
+            self.difficulty = Some(Difficulty::of(self.hand.tiles(), &self.dictionary));
+            return;
+        }
+        self.pile = Pile::new(self.distribution.create_pile(
+            self.pile_size,
+            &mut self.rng,
+            &self.rules,
+        ));
+        self.hand = Hand::default();
+        self.pile
+            .deal(&mut self.hand, self.hand_size, &mut self.rng)
+            .unwrap();
+        self.hand.forget_new_tiles();
+        self.starting_hand = self.hand.tiles().to_vec();
+        if let Some(bot) = &mut self.bot {
+            bot.reset();
+            let amount: usize = self.hand_size.min(self.pile.len());
+            self.pile
+                .deal(&mut bot.hand, amount, &mut self.rng)
+                .unwrap();
+        }
+        // Endless games only use the pile for the starting hand, then draw from the distribution.
+        if self.endless {
+            self.pile = Pile::default();
+        }
+        self.difficulty = Some(Difficulty::of(self.hand.tiles(), &self.dictionary));
+    }
+
+    /// Picks the letter that scores triple this game, from the seed so anyone playing it
+    /// gets the same one.
+    fn pick_hot_letter(&mut self) {
+        let letters: Vec<char> = self
+            .distribution
+            .alphabet()
+            .filter(|x: &char| self.rules.allows_tile(*x))
+            .collect();
+        let hot: Option<char> = (self.hot_letters && !letters.is_empty()).then(|| {
+            letters[usize::try_from(mix(self.seed) % letters.len() as u64).unwrap_or_default()]
+        });
+        self.scoring.set_hot(hot);
+        self.camera.hot = hot;
+    }
+
+    /// Starts the game clock.
+    fn start(&mut self) {
+        self.phase = GamePhase::Playing;
+        self.game_start = Instant::now();
+        self.weigh_hand();
+        if let Some(letter) = self.scoring.hot() {
+            self.announce(
+                format!("Hot letter: '{letter}' scores triple this game!")
+                    .set_style(Style::new().fg(Color::Magenta)),
+                Verbosity::Important,
+            );
+        }
+    }
+
+    /// Works out which tiles in the hand are dead weight, if the hand is being grouped.
+    ///
+    /// This goes through the whole dictionary, so it's only done once a move is made.
+    fn weigh_hand(&mut self) {
+        if self.group_hand {
+            self.dead_weight =
+                analysis::dead_weight(self.hand.tiles(), &self.camera.grid, &self.dictionary);
+        }
+    }
+
+    /// How many hints have been taken this game.
+    fn hints_used(&self) -> usize {
+        self.actions.iter().filter(|x| **x == Action::Hint).count()
+    }
+
+    /// How long the game has been played for, not counting time spent paused.
+    fn elapsed(&self) -> Duration {
+        if self.phase == GamePhase::Setup {
+            return Duration::ZERO;
+        }
+
+        let now: Instant = self
+            .game_end
+            .or(self.paused_since)
+            .unwrap_or_else(Instant::now);
+        now.duration_since(self.game_start)
+            .saturating_sub(self.paused_for)
+    }
+
+    /// Whether the pile is too small to give every player a tile.
+    ///
+    /// This isn't the same as the pile being empty once there's more than one player.
+    const fn is_endgame(&self) -> bool {
+        !self.endless && self.pile.len() < self.players
+    }
+
+    /// Draws a tile into the hand, from the pile, or from the distribution in endless games.
+    fn draw_tiles(&mut self, amount: usize) -> Result<(), Error> {
+        if !self.endless {
+            return self.pile.deal(&mut self.hand, amount, &mut self.rng);
+        }
+        for _ in 0..amount {
+            let tile: char = self.distribution.pull_endless(&mut self.rng, &self.rules);
+            self.hand.draw(tile);
+        }
+        Ok(())
+    }
+
+    /// Draws a tile into the bot's hand the same way, if there's a bot.
+    fn draw_for_bot(&mut self) -> Result<(), Error> {
+        let Some(bot) = &mut self.bot else {
+            return Ok(());
+        };
+        if self.endless {
+            bot.hand
+                .draw(self.distribution.pull_endless(&mut self.rng, &self.rules));
+            return Ok(());
+        }
+        self.pile.deal(&mut bot.hand, 1, &mut self.rng)
+    }
+
+    /// Adds to (or takes away from) the score.
+    fn change_score(&mut self, delta: i64) {
+        self.score += delta;
+        self.last_delta = Some((delta, Instant::now()));
+    }
+
+    /// How long a frame can take to draw before it counts as slow.
+    const SLOW_FRAME: Duration = Duration::from_millis(100);
+
+    /// How many slow frames in a row it takes to turn on low-resource mode.
+    const SLOW_FRAMES_ALLOWED: usize = 10;
+
+    /// Goes easy on the terminal or not: fewer redraws, plainer tiles and a smaller view of the board.
+    fn set_low_resource(&mut self, on: bool) {
+        self.low_resource = on;
+        self.camera.plain = on;
+    }
+
+    /// Turns on low-resource mode once frames have been slow to draw for a while.
+    fn watch_frame_time(&mut self) {
+        if self.low_resource {
+            return;
+        }
+        if self.metrics.frame_time >= Self::SLOW_FRAME {
+            self.metrics.slow_frames += 1;
+        } else {
+            self.metrics.slow_frames = 0;
+        }
+        if self.metrics.slow_frames >= Self::SLOW_FRAMES_ALLOWED {
+            self.set_low_resource(true);
+            self.announce(
+                "This terminal is slow to draw, so low-resource mode is on."
+                    .set_style(Style::new().fg(Color::Yellow)),
+                Verbosity::Important,
+            );
+        }
+    }
+
+    /// Swaps in the config's theme, if the file has changed.
+    fn reload_theme(&mut self, watcher: &mut ThemeWatcher) {
+        match watcher.poll() {
+            Some(Ok(theme)) => self.camera.theme = theme,
+            Some(Err(exception)) => self.announce(
+                exception.to_string().set_style(Style::new().fg(Color::Red)),
+                Verbosity::Important,
+            ),
+            None => (),
+        }
+    }
+
+    /// Lets a key held back for a double tap go through, once it's too late for a second tap.
+    fn release_held_key(&mut self) -> ControlFlow<()> {
+        match self.keymap.release(Instant::now()) {
+            Some(key) => {
+                let response: Result<EventResponse, Error> = key_controls(self, key);
+                self.respond(response)
+            }
+            None => ControlFlow::Continue(()),
+        }
+    }
+
+    /// How long to wait for input before drawing again, or letting a held key go through.
+    fn input_wait(&self) -> Duration {
+        self.keymap
+            .until_release(Instant::now())
+            .map_or(self.redraw_interval(), |x: Duration| {
+                x.min(self.redraw_interval())
+            })
+    }
+
+    /// How long to wait for input before drawing again anyway, to keep the clock moving.
+    const fn redraw_interval(&self) -> Duration {
+        if self.low_resource {
+            Duration::from_millis(500)
+        } else {
+            Duration::from_millis(50)
+        }
+    }
+
+    /// Shows a status, if the verbosity is high enough for it.
+    fn announce(&mut self, status: Span<'static>, verbosity: Verbosity) {
+        if self.verbosity >= verbosity {
+            self.status = status;
+        }
+    }
+
+    /// Acts on what handling an event came back with, breaking if the game should quit.
+    fn respond(&mut self, response: Result<EventResponse, Error>) -> ControlFlow<()> {
+        match response {
+            Ok(EventResponse::Quit) => return ControlFlow::Break(()),
+            Ok(EventResponse::ChangeStatus(new_status)) => {
+                self.announce(new_status, Verbosity::Important);
+            }
+            Ok(EventResponse::Announce(new_status)) => {
+                self.announce(new_status, Verbosity::Everything);
+            }
+            Ok(EventResponse::Pass) => (),
+            Err(exception) => {
+                self.announce(
+                    exception.to_string().set_style(Style::new().fg(Color::Red)),
+                    Verbosity::Important,
+                );
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Sounds the buzzer once a timed game runs out of time.
+    fn buzz_if_time_up(&mut self) {
+        if self.phase != GamePhase::Playing
+            || self
+                .time_limit
+                .is_none_or(|limit: Duration| self.elapsed() < limit)
+        {
+            return;
+        }
+        self.act(Action::Buzzer);
+    }
+
+    /// Peels once an auto-peel's grace period is up.
+    fn peel_if_due(&mut self) {
+        if self
+            .pending_peel
+            .is_none_or(|due: Instant| Instant::now() < due)
+        {
+            return;
+        }
+        self.pending_peel = None;
+        if self.phase == GamePhase::Playing && self.hand.is_empty() {
+            self.act(Action::Peel);
+        }
+    }
+
+    /// Gives the bot its turn, once it's had long enough to think it over.
+    fn bot_move_if_due(&mut self) {
+        let elapsed: Duration = self.elapsed();
+        let Some(bot) = &mut self.bot else {
+            return;
+        };
+        if self.phase != GamePhase::Playing || self.paused_since.is_some() || !bot.is_due(elapsed) {
+            return;
+        }
+        bot.next_move = elapsed + bot.delay;
+        if bot.play(&self.dictionary, &self.scoring, &self.rules) == Turn::Peel {
+            self.act(Action::BotPeel);
+        }
+    }
+
+    /// Makes a move that didn't come from a key press.
+    fn act(&mut self, action: Action) {
+        let response: Result<EventResponse, Error> =
+            action::apply(self, action).and_then(|response: EventResponse| {
+                if self.phase == GamePhase::Finished {
+                    self.keep_finished_game()?;
+                }
+                Ok(response)
+            });
+        let _ = self.respond(response);
+    }
+
+    /// Exports a finished game, puts it on the high score table, writes its words in the journal,
+    /// and notes how its starting hand went for practice.
+    fn keep_finished_game(&mut self) -> Result<(), Error> {
+        notation::export(
+            self.seed,
+            &self.rules,
+            &self.actions,
+            &self.action_times,
+            self.hints,
+            self.camera.stats.checksum,
+        )?;
+        self.standing = Some(scores::record(self, Path::new(SCORES_PATH))?);
+        if action::validate_board(self).is_ok() {
+            journal::record(
+                &self.camera.stats.words,
+                &self.dictionary,
+                Path::new(JOURNAL_PATH),
+            )?;
+        }
+        if let Some(attempts) = practice::record(self, Path::new(STRUGGLES_PATH))? {
+            self.drill_attempts = attempts;
+        }
+        Ok(())
+    }
+
+    /// Stops the game clock and hides the game until it's resumed.
+    fn pause(&mut self) {
+        if self.paused_since.is_none() && self.phase == GamePhase::Playing {
+            self.paused_since = Some(Instant::now());
+            self.pause_choice = PauseChoice::default();
+        }
+    }
+
+    /// Pauses the game if nothing's been pressed for a while.
+    fn pause_if_idle(&mut self) {
+        if !self.idle_timeout.is_zero() && self.last_input.elapsed() >= self.idle_timeout {
+            self.pause();
+        }
+    }
+
+    /// Starts the game clock back up.
+    fn resume(&mut self) {
+        if let Some(paused_since) = self.paused_since.take() {
+            self.paused_for += paused_since.elapsed();
+        }
+    }
 }
 
 #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
 fn main() -> Result<()> {
     color_eyre::install()?;
 
-    let dictionary_list: Vec<PathBuf> = list_dictionaries();
-    let dictionary: HashSet<String> = get_dictionary(&dictionary_list[0])?;
-
-    let grid: Grid<Option<char>> = Grid::default();
-    let mut state: GameState = GameState {
-        dictionary,
-        camera: Camera::new(grid),
-        distribution: Distribution::Bananagrams,
-        tileset: {
-            let mut pile: Vec<char> = Distribution::Bananagrams.create_pile(144);
-            let mut hand: Vec<char> = Distribution::pull_from_pile(&mut pile, 21).unwrap();
-            hand.sort_unstable();
-            (pile, hand)
-        },
-        game_start: Instant::now(),
-        game_end: None,
-        score: 0,
-        scoretable: HashMap::from([
-            ('a', 1),
-            ('b', 3),
-            ('c', 3),
-            ('d', 2),
-            ('e', 1),
-            ('f', 4),
-            ('g', 2),
-            ('h', 4),
-            ('i', 1),
-            ('j', 8),
-            ('k', 5),
-            ('l', 1),
-            ('m', 3),
-            ('n', 1),
-            ('o', 1),
-            ('p', 3),
-            ('q', 10),
-            ('r', 1),
-            ('s', 1),
-            ('t', 1),
-            ('u', 1),
-            ('v', 4),
-            ('w', 4),
-            ('x', 8),
-            ('y', 4),
-            ('z', 10),
-        ]),
-        status: "".set_style(Style::new().fg(Color::Black).bg(Color::White)),
-    };
+    let cli: Cli = Cli::parse();
+    let mut config: Config = Config::load(cli.config.as_deref())?;
+    cli.override_config(&mut config);
+    config.check()?;
+    if let Some(command) = &cli.command {
+        return run_command(command, &config);
+    }
 
     let mut terminal = ratatui::init();
-    loop {
-        terminal
+    let mut state: GameState = match set_up(&mut terminal, &config, &cli) {
+        Ok(Some(state)) => state,
+        other => {
+            ratatui::restore();
+            return other.map(|_| ());
+        }
+    };
+    let sticky_modifiers: bool = state.keymap.sticky_modifiers;
+
+    // Lone modifier presses are only reported with keyboard enhancement turned on.
+    let enhanced: bool = sticky_modifiers && supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced {
+        execute!(
+            stdout(),
+            PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+            )
+        )?;
+    } else if sticky_modifiers {
+        state.status =
+            "This terminal can't report modifier keys on their own, so sticky keys are off."
+                .set_style(Style::new().fg(Color::Red));
+    }
+    if config.mouse {
+        execute!(stdout(), EnableMouseCapture)?;
+    }
+    let mut outputs: Outputs = Outputs {
+        recorder: match &cli.record {
+            Some(path) => {
+                let size: Size = terminal.size()?;
+                Some(Recorder::create(path, size.width, size.height)?)
+            }
+            None => None,
+        },
+        score_cam: cli.score_cam.as_deref().map(ScoreCam::new),
+        profile: cli
+            .profile_render
+            .as_deref()
+            .map(RenderProfile::create)
+            .transpose()?,
+    };
+    let mut theme_watcher: Option<ThemeWatcher> = ThemeWatcher::new(cli.config.as_deref());
+    'game: loop {
+        if let Some(watcher) = &mut theme_watcher {
+            state.reload_theme(watcher);
+        }
+        state.pause_if_idle();
+        state.buzz_if_time_up();
+        state.peel_if_due();
+        let response: Result<EventResponse, Error> = replay::play_due(&mut state);
+        let _ = state.respond(response);
+        state.bot_move_if_due();
+        let drawn_at: Instant = Instant::now();
+        let frame = terminal
             .draw(|frame| draw(frame, &mut state))
             .expect("failed to draw frame");
+        state.metrics.frame_time = drawn_at.elapsed();
+        outputs.frame(&mut state, frame.buffer);
+        state.watch_frame_time();
 
-        if event::poll(Duration::from_millis(50))? {
-            match event_handler(&mut state) {
-                Ok(response) => match response {
-                    EventResponse::Quit => break,
-                    EventResponse::ChangeStatus(new_status) => state.status = new_status,
-                    EventResponse::Pass => (),
-                },
-                Err(exception) => {
-                    state.status = exception.to_string().set_style(Style::new().fg(Color::Red));
-                }
+        if state.release_held_key().is_break() {
+            break 'game;
+        }
+        if !event::poll(state.input_wait())? {
+            continue;
+        }
+        // Take everything that's waiting, not just one event, so a slow frame
+        // doesn't leave fast typing behind. It's all handled in order before the next draw.
+        let mut queue: Vec<Event> = Vec::new();
+        while event::poll(Duration::ZERO)? {
+            queue.push(event::read()?);
+        }
+        state.metrics.input_depth = queue.len();
+        state.metrics.input_peak = state.metrics.input_peak.max(queue.len());
+        let handled_at: Instant = Instant::now();
+        for event in queue {
+            let response: Result<EventResponse, Error> = event_handler(&mut state, &event);
+            if state.respond(response).is_break() {
+                break 'game;
             }
         }
+        state.metrics.input_time = handled_at.elapsed();
+    }
+    if enhanced {
+        execute!(stdout(), PopKeyboardEnhancementFlags)?;
+    }
+    if config.mouse {
+        execute!(stdout(), DisableMouseCapture)?;
     }
     ratatui::restore();
 
     Ok(())
 }
 
+/// Picks a save to carry on with or the dictionaries to play with, then sets up the game.
+///
+/// Returns nothing if the player quits before there's a game.
+fn set_up(terminal: &mut DefaultTerminal, config: &Config, cli: &Cli) -> Result<Option<GameState>> {
+    // A save is picked before a dictionary, so quitting the save slots doesn't ask for one.
+    let resume: Option<(String, Save)> = if cli.resume {
+        let Some(slot) = save::browse(terminal, Path::new(SAVE_DIRECTORY))? else {
+            return Ok(None);
+        };
+        Some(slot)
+    } else {
+        None
+    };
+    // Nothing can be dealt until there's a dictionary, so pick one first, unless the config already has.
+    let mut paths: Vec<PathBuf> = config
+        .dictionary
+        .iter()
+        .chain(&config.dictionaries)
+        .cloned()
+        .collect();
+    if paths.is_empty() {
+        let dictionary_list: Vec<PathBuf> = list_dictionaries();
+        let Some(picked) = pick_dictionary(terminal, &dictionary_list)? else {
+            return Ok(None);
+        };
+        paths = picked
+            .into_iter()
+            .map(|index: usize| dictionary_list[index].clone())
+            .collect();
+    }
+    new_game(
+        load_dictionary(&paths, config.normalization)?,
+        config,
+        cli,
+        resume,
+    )
+    .map(Some)
+}
+
+/// Everything besides the terminal that's written to as the game goes.
+struct Outputs {
+    recorder: Option<Recorder<BufWriter<File>>>,
+    score_cam: Option<ScoreCam>,
+    profile: Option<RenderProfile<BufWriter<File>>>,
+}
+
+impl Outputs {
+    /// Passes a drawn frame and the state it came from along to every output.
+    fn frame(&mut self, state: &mut GameState, buffer: &Buffer) {
+        let mut result: Result<(), Error> = Ok(());
+        if let Some(recorder) = &mut self.recorder {
+            result = result.and(recorder.frame(buffer));
+        }
+        if let Some(score_cam) = &mut self.score_cam {
+            result = result.and(score_cam.update(state));
+        }
+        if let Some(profile) = &mut self.profile {
+            result = result.and(profile.frame(state));
+        }
+        state.camera.reset_scan_times();
+        if let Err(exception) = result {
+            state.announce(
+                exception.to_string().set_style(Style::new().fg(Color::Red)),
+                Verbosity::Important,
+            );
+        }
+
+        if std::mem::take(&mut state.screenshot_requested) {
+            let status: Span<'static> = match screenshot::save(buffer) {
+                Ok(path) => format!("Saved a screenshot to {}.ans/.html.", path.display())
+                    .set_style(Style::new().fg(Color::Green)),
+                Err(exception) => exception.to_string().set_style(Style::new().fg(Color::Red)),
+            };
+            state.announce(status, Verbosity::Important);
+        }
+    }
+}
+
+/// Does something other than play a game.
+fn run_command(command: &Command, config: &Config) -> Result<()> {
+    match command {
+        Command::View { file } => {
+            let grid: Grid<Option<char>> = viewer::load_board(file)?;
+            let mut terminal = ratatui::init();
+            let result = viewer::run(&mut terminal, grid);
+            ratatui::restore();
+            Ok(result?)
+        }
+        Command::Journal => {
+            let journal: Journal = Journal::load(Path::new(JOURNAL_PATH))?;
+            let mut terminal = ratatui::init();
+            let result = journal::run(&mut terminal, journal);
+            ratatui::restore();
+            Ok(result?)
+        }
+        Command::Dict {
+            command: DictCommand::Stats { path },
+        } => {
+            let dictionary: Dictionary = get_dictionary(path, config.normalization)?;
+            print!("{}", dict_stats::report(&dictionary, config));
+            Ok(())
+        }
+    }
+}
+
+/// Reads the picked dictionaries as one, with the house dictionary on top.
+fn load_dictionary(paths: &[PathBuf], normalization: Normalization) -> Result<Dictionary> {
+    let mut dictionary: Dictionary = get_dictionaries(paths, normalization)?;
+    dictionary.extend(
+        dictionary::load_house_words(Path::new(HOUSE_PATH))
+            .map_err(|x| Error::HouseDictionaryFailed(x.to_string()))?,
+    );
+    Ok(dictionary)
+}
+
+/// Sets up the game picked on the command line, or carries on with the save slot picked.
+fn new_game(
+    dictionary: Dictionary,
+    config: &Config,
+    cli: &Cli,
+    resume: Option<(String, Save)>,
+) -> Result<GameState> {
+    let record: Option<GameRecord> = cli.import.as_deref().map(notation::import).transpose()?;
+    let challenge: Option<Challenge> = (cli.mode == Mode::Weekly).then(Challenge::current);
+    let seed: u64 = record
+        .as_ref()
+        .map(|x: &GameRecord| x.seed)
+        .or(challenge.as_ref().map(|x: &Challenge| x.seed))
+        .or(cli.seed)
+        .unwrap_or_else(rand::random);
+    // Picked from the seed too, so sharing the seed shares the drill.
+    let drill: Option<Drill> = if cli.mode == Mode::Practice {
+        Some(practice::pick(Path::new(STRUGGLES_PATH), seed)?)
+    } else {
+        cli.drill
+            .as_deref()
+            .map(drill::load_drills)
+            .transpose()?
+            .map(|drills: Vec<Drill>| drill::pick(&drills, seed))
+    };
+    let mut state: GameState = GameState::new(dictionary, config, seed);
+    state.keymap.sticky_modifiers = cli.sticky_keys;
+    state.keymap.debounce = Duration::from_millis(cli.debounce);
+    state.verbosity = cli.verbosity;
+    match cli.mode {
+        // Practice hands are dealt as drills below.
+        Mode::Normal | Mode::Weekly | Mode::Practice => (),
+        Mode::Casual => {
+            state.casual = true;
+            state.deal();
+        }
+        Mode::Endless => {
+            state.endless = true;
+            state.deal();
+        }
+        Mode::Blitz => state.time_limit = Some(Duration::from_mins(cli.minutes)),
+        // Played with endless draws, so the pile never ends the game first.
+        Mode::Target => {
+            state.endless = true;
+            state.target_score = Some(cli.target);
+            state.deal();
+        }
+        Mode::Bot => {
+            state.bot = Some(Bot::new(Duration::from_secs(cli.bot_delay)));
+            state.players = 2;
+            state.deal();
+        }
+    }
+    if let Some(drill) = drill {
+        state.drill_attempts = Struggles::load(Path::new(STRUGGLES_PATH))?.attempts(&drill);
+        state.drill = Some(drill);
+        state.deal();
+    }
+    if let Some(challenge) = challenge {
+        state.rules = challenge.rules.clone();
+        state.challenge = Some(challenge);
+        state.deal();
+    }
+    if let Some(record) = record {
+        state.rules = record.rules;
+        state.hints = record.hints.unwrap_or(state.hints);
+        state.deal();
+        state.start();
+        state.phase = GamePhase::Reviewing;
+        state.playback_times = replay::pace(&record.times, record.actions.len());
+        state.playback = record.actions.into();
+        state.playback_checksum = record.checksum;
+    }
+    if let Some((slot, resume)) = resume {
+        resume.restore(&mut state);
+        state.save_slot = Some(slot);
+    }
+
+    Ok(state)
+}
+
+/// What part of the game is happening, which decides what the player can do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GamePhase {
+    /// The tiles are dealt, but the clock hasn't started.
+    Setup,
+    /// The game is being played.
+    Playing,
+    /// The game is over. The board can be looked around, but not changed.
+    Finished,
+    /// An imported game is being stepped through.
+    Reviewing,
+}
+
+/// The options on the pause screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum PauseChoice {
+    #[default]
+    Resume,
+    Quit,
+}
+
 #[derive(PartialEq, Eq, Clone)]
 enum EventResponse {
     Pass,
     ChangeStatus(Span<'static>),
+    /// A status for something minor, like a placement, only shown at the highest verbosity.
+    Announce(Span<'static>),
     Quit,
 }
 
-#[derive(Debug, thiserror::Error, Clone)]
-pub enum Error {
-    #[error("Not all words are connected!")]
-    WordsNotConnected,
-    #[error("Invalid word \"{0}\"!")]
-    InvalidWord(String),
-    #[error("The pile's all out of tiles, or there isn't enough to pull!")]
-    NoMoreTiles,
-    #[error("You still have tiles in your hand!")]
-    HandHasTiles,
+/// How much gets announced in the status line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+enum Verbosity {
+    /// Nothing, not even errors.
+    Silent,
+    /// Peels, dumps, errors, and replies to commands.
+    #[default]
+    Important,
+    /// Every placement and pick-up as well.
+    Everything,
+}
+
+impl FromStr for Verbosity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "silent" => Ok(Self::Silent),
+            "important" => Ok(Self::Important),
+            "everything" => Ok(Self::Everything),
+            _ => Err(Error::UnknownVerbosity(s.to_string())),
+        }
+    }
 }
@@ -0,0 +1,383 @@
+//! A compact, human-readable notation for a game's actions.
+//!
+//! Each line of a notation file is one action:
+//! - `P e 3,-2` places an 'e' at (3, -2)
+//! - `U 3,-2` picks up the tile at (3, -2)
+//! - `U 3,-2 1,0` picks up the tile at (3, -2) and the run of tiles to its right
+//! - `M 0,0 2,1 3,-1` moves every tile from (0, 0) to (2, 1) three right and one down
+//! - `D q` dumps a 'q'
+//! - `!` peels
+//! - `T` is the buzzer at the end of a timed game
+//! - `?` asks for a hint
+//! - `B` is the bot peeling
+//!
+//! An action can be followed by `@<ms>`, how many milliseconds into the game it
+//! was made, so the game can be played back at its own pace.
+//!
+//! Lines starting with `#` are comments, apart from the headers: `# seed <n>`
+//! records the seed the game was dealt from, each `# mutator <name>`
+//! records a mutator that was in play, `# hints <per game> <cost percent>`
+//! records the hint budget, and `# checksum <hex>` records the
+//! checksum of the board the game ended on, so a replay that ends up somewhere
+//! else is caught.
+
+use std::{
+    fmt::{Display, Write as _},
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    Error,
+    action::{Action, HintBudget},
+    grid::{Coordinate, Grid},
+    rules::{MUTATORS, Mutator, Rules},
+};
+
+/// The folder exported games are written to.
+const EXPORT_DIRECTORY: &str = "games";
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Place(letter, coordinate) => {
+                write!(f, "P {letter} {},{}", coordinate.0, coordinate.1)
+            }
+            Self::PickUp(coordinate) => write!(f, "U {},{}", coordinate.0, coordinate.1),
+            Self::PickUpRun(coordinate, direction) => write!(
+                f,
+                "U {},{} {},{}",
+                coordinate.0, coordinate.1, direction.0, direction.1
+            ),
+            Self::Move(min, max, offset) => write!(
+                f,
+                "M {},{} {},{} {},{}",
+                min.0, min.1, max.0, max.1, offset.0, offset.1
+            ),
+            Self::Dump(letter) => write!(f, "D {letter}"),
+            Self::Peel => write!(f, "!"),
+            Self::Buzzer => write!(f, "T"),
+            Self::Hint => write!(f, "?"),
+            Self::BotPeel => write!(f, "B"),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::ImportFailed(format!("\"{s}\" isn't a valid move"));
+        let parse_letter = |letter: &str| -> Result<char, Error> {
+            let mut chars = letter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(letter), None) => Ok(letter),
+                _ => Err(invalid()),
+            }
+        };
+        let parse_coordinate = |coordinate: &str| -> Result<Coordinate, Error> {
+            let (x, y) = coordinate.split_once(',').ok_or_else(invalid)?;
+            Ok(Coordinate(
+                x.trim().parse().map_err(|_| invalid())?,
+                y.trim().parse().map_err(|_| invalid())?,
+            ))
+        };
+
+        match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["P", letter, coordinate] => Ok(Self::Place(
+                parse_letter(letter)?,
+                parse_coordinate(coordinate)?,
+            )),
+            ["U", coordinate] => Ok(Self::PickUp(parse_coordinate(coordinate)?)),
+            ["U", coordinate, direction] => {
+                let direction: Coordinate = parse_coordinate(direction)?;
+                // Runs only go one cell at a time, along a row or column.
+                if direction.0.abs() + direction.1.abs() != 1 {
+                    return Err(invalid());
+                }
+                Ok(Self::PickUpRun(parse_coordinate(coordinate)?, direction))
+            }
+            ["M", min, max, offset] => {
+                let (min, max): (Coordinate, Coordinate) =
+                    (parse_coordinate(min)?, parse_coordinate(max)?);
+                // Corners go bottom-left then top-right, like `Grid::bounding_box` gives.
+                if min.0 > max.0 || min.1 > max.1 {
+                    return Err(invalid());
+                }
+                Ok(Self::Move(min, max, parse_coordinate(offset)?))
+            }
+            ["D", letter] => Ok(Self::Dump(parse_letter(letter)?)),
+            ["!"] => Ok(Self::Peel),
+            ["T"] => Ok(Self::Buzzer),
+            ["?"] => Ok(Self::Hint),
+            ["B"] => Ok(Self::BotPeel),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Everything needed to play a game back.
+#[derive(Debug, Clone, Default)]
+pub struct GameRecord {
+    pub seed: u64,
+    pub rules: Rules,
+    pub actions: Vec<Action>,
+    /// How far into the game each action was made. Empty unless every action was timed.
+    pub times: Vec<Duration>,
+    /// The hint budget the game was played with, if it was written down.
+    pub hints: Option<HintBudget>,
+    /// The checksum of the board the game ended on, if it was written down.
+    pub checksum: Option<u64>,
+}
+
+impl GameRecord {
+    /// The board as the game left it.
+    ///
+    /// Only placements and pick-ups are replayed, so a blank shows as the letter it was
+    /// played as, not in uppercase like it would in the game.
+    pub fn board(&self) -> Grid<Option<char>> {
+        let mut grid: Grid<Option<char>> = Grid::default();
+        for action in &self.actions {
+            match *action {
+                Action::Place(letter, coordinate) => {
+                    grid[coordinate].get_or_insert(letter);
+                }
+                Action::PickUp(coordinate) => {
+                    grid.remove(coordinate);
+                }
+                Action::PickUpRun(mut coordinate, direction) => {
+                    while grid.remove(coordinate).is_some() {
+                        coordinate = coordinate.overflowing_add(direction).0;
+                    }
+                }
+                Action::Move(min, max, offset) => {
+                    let _ = grid.move_region((min, max), offset);
+                }
+                Action::Dump(..)
+                | Action::Peel
+                | Action::Buzzer
+                | Action::Hint
+                | Action::BotPeel => (),
+            }
+        }
+        grid
+    }
+}
+
+/// Reads a game written in notation.
+pub fn from_notation(notation: &str) -> Result<GameRecord, Error> {
+    let mut seed: Option<u64> = None;
+    let mut rules: Rules = Rules::default();
+    let mut actions: Vec<Action> = Vec::new();
+    let mut times: Vec<Duration> = Vec::new();
+    let mut hints: Option<HintBudget> = None;
+    let mut checksum: Option<u64> = None;
+
+    for line in notation.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(value) = comment.trim().strip_prefix("seed ") {
+                seed =
+                    Some(value.trim().parse().map_err(|_| {
+                        Error::ImportFailed(format!("\"{value}\" isn't a valid seed"))
+                    })?);
+            } else if let Some(value) = comment.trim().strip_prefix("checksum ") {
+                checksum = Some(u64::from_str_radix(value.trim(), 16).map_err(|_| {
+                    Error::ImportFailed(format!("\"{value}\" isn't a valid checksum"))
+                })?);
+            } else if let Some(value) = comment.trim().strip_prefix("hints ") {
+                let invalid =
+                    || Error::ImportFailed(format!("\"{value}\" isn't a valid hint budget"));
+                let (per_game, cost_percent) = value.trim().split_once(' ').ok_or_else(invalid)?;
+                hints = Some(HintBudget {
+                    per_game: per_game.parse().map_err(|_| invalid())?,
+                    cost_percent: cost_percent.trim().parse().map_err(|_| invalid())?,
+                });
+            } else if let Some(value) = comment.trim().strip_prefix("mutator ") {
+                rules.mutators.push(
+                    *MUTATORS
+                        .iter()
+                        .find(|mutator: &&Mutator| mutator.to_string() == value.trim())
+                        .ok_or_else(|| {
+                            Error::ImportFailed(format!("\"{value}\" isn't a known mutator"))
+                        })?,
+                );
+            }
+        } else if !line.is_empty() {
+            let (action, time): (&str, Option<&str>) = match line.rsplit_once(" @") {
+                Some((action, time)) => (action, Some(time)),
+                None => (line, None),
+            };
+            actions.push(action.parse()?);
+            if let Some(time) = time {
+                times.push(Duration::from_millis(time.trim().parse().map_err(
+                    |_| Error::ImportFailed(format!("\"{time}\" isn't a valid time")),
+                )?));
+            }
+        }
+    }
+    if times.len() != actions.len() {
+        times.clear();
+    }
+
+    Ok(GameRecord {
+        seed: seed.ok_or_else(|| Error::ImportFailed("the seed is missing".to_string()))?,
+        rules,
+        actions,
+        times,
+        hints,
+        checksum,
+    })
+}
+
+/// Reads a notation file.
+pub fn import(path: &Path) -> Result<GameRecord, Error> {
+    from_notation(
+        &fs::read_to_string(path)
+            .map_err(|exception| Error::ImportFailed(exception.to_string()))?,
+    )
+}
+
+/// Writes a game out in notation, along with the checksum of the board it's left on.
+///
+/// Actions are only timed if there's a time for every one.
+pub fn to_notation(
+    seed: u64,
+    rules: &Rules,
+    actions: &[Action],
+    times: &[Duration],
+    hints: HintBudget,
+    checksum: u64,
+) -> String {
+    let mut output: String = format!("# seed {seed}\n");
+    for mutator in &rules.mutators {
+        writeln!(&mut output, "# mutator {mutator}").unwrap();
+    }
+    writeln!(
+        &mut output,
+        "# hints {} {}",
+        hints.per_game, hints.cost_percent
+    )
+    .unwrap();
+    writeln!(&mut output, "# checksum {checksum:016x}").unwrap();
+    if times.len() == actions.len() {
+        for (action, time) in actions.iter().zip(times) {
+            writeln!(&mut output, "{action} @{}", time.as_millis()).unwrap();
+        }
+    } else {
+        for action in actions {
+            writeln!(&mut output, "{action}").unwrap();
+        }
+    }
+    output
+}
+
+/// Exports a game to a new notation file in `./games/`, returning its path.
+pub fn export(
+    seed: u64,
+    rules: &Rules,
+    actions: &[Action],
+    times: &[Duration],
+    hints: HintBudget,
+    checksum: u64,
+) -> Result<PathBuf, Error> {
+    let timestamp: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_secs());
+    let path: PathBuf = PathBuf::from(EXPORT_DIRECTORY).join(format!("{seed}-{timestamp}.gvn"));
+
+    fs::create_dir_all(EXPORT_DIRECTORY)
+        .and_then(|()| {
+            fs::write(
+                &path,
+                to_notation(seed, rules, actions, times, hints, checksum),
+            )
+        })
+        .map_err(|exception| Error::ExportFailed(exception.to_string()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{from_notation, to_notation};
+    use crate::{
+        action::{Action, HintBudget},
+        grid::{Coordinate, Grid},
+        rules::{Mutator, Rules},
+    };
+
+    #[test]
+    fn test_round_trip() {
+        let actions: Vec<Action> = vec![
+            Action::Place('e', Coordinate(3, -2)),
+            Action::PickUp(Coordinate(3, -2)),
+            Action::PickUpRun(Coordinate(3, -2), Coordinate(0, -1)),
+            Action::Move(Coordinate(0, 0), Coordinate(2, 1), Coordinate(3, -1)),
+            Action::Dump('q'),
+            Action::Peel,
+            Action::Buzzer,
+            Action::Hint,
+            Action::BotPeel,
+        ];
+        let rules: Rules = Rules {
+            mutators: vec![Mutator::NoLetter('s')],
+        };
+        let hints: HintBudget = HintBudget {
+            per_game: 2,
+            cost_percent: 15,
+        };
+        let notation: String = to_notation(42, &rules, &actions, &[], hints, 0xbeef);
+        assert_eq!(
+            notation,
+            "# seed 42\n# mutator No 's' tiles\n# hints 2 15\n# checksum 000000000000beef\nP e 3,-2\nU 3,-2\nU 3,-2 0,-1\nM 0,0 2,1 3,-1\nD q\n!\nT\n?\nB\n"
+        );
+
+        let record = from_notation(&notation).unwrap();
+        assert_eq!(record.seed, 42);
+        assert_eq!(record.rules.mutators, rules.mutators);
+        assert_eq!(record.actions, actions);
+        assert_eq!(record.hints, Some(hints));
+        assert_eq!(record.checksum, Some(0xbeef));
+        assert!(record.times.is_empty());
+
+        // Timed actions keep their times, to the millisecond.
+        let times: Vec<Duration> = (1..=9)
+            .map(|x: u64| Duration::from_millis(x * 1500))
+            .collect();
+        let notation: String = to_notation(42, &rules, &actions, &times, hints, 0xbeef);
+        assert!(notation.ends_with("\n? @12000\nB @13500\n"));
+        let record = from_notation(&notation).unwrap();
+        assert_eq!(record.actions, actions);
+        assert_eq!(record.times, times);
+        // Games written before checksums or hint budgets were recorded still load.
+        let record = from_notation("# seed 42\n!\n").unwrap();
+        assert_eq!((record.checksum, record.hints), (None, None));
+        assert!(from_notation("# seed 42\n# hints 2\n").is_err());
+    }
+
+    #[test]
+    fn test_board() {
+        let record =
+            from_notation("# seed 1\nP c 0,0\nP a 1,0\nP x 2,0\nU 2,0\nP t 2,0\n!\n").unwrap();
+        let board: Grid<Option<char>> = record.board();
+        assert_eq!(board[Coordinate(2, 0)], Some('t'));
+        assert_eq!(board.occupied_cells().count(), 3);
+
+        let record = from_notation("# seed 1\nP c 0,0\nP a 1,0\nM 0,0 1,0 0,2\n").unwrap();
+        assert_eq!(record.board()[Coordinate(1, 2)], Some('a'));
+    }
+
+    #[test]
+    fn test_rejects_bad_moves() {
+        assert!(from_notation("# seed 1\nP ee 0,0").is_err());
+        assert!(from_notation("# seed 1\nU 3000000000,0").is_err());
+        assert!(from_notation("# seed 1\nU 0,0 1,1").is_err());
+        assert!(from_notation("# seed 1\nM 2,0 0,0 1,0").is_err());
+        assert!(from_notation("P e 0,0").is_err());
+        assert!(from_notation("# seed 1\n# checksum banana").is_err());
+        assert!(from_notation("# seed 1\n! @soon").is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Practicing the starting hands that gave the most trouble before.
+//!
+//! Every finished game notes its starting hand, how long it took to get to the
+//! first peel, and how many tiles were traded in on the way. Practice mode picks
+//! one of the worst of them and deals it again as a drill, so it can be played
+//! until it stops being a problem.
+//!
+//! Drills are kept track of too, by rack: how many times each was played, and how
+//! many of those played out every tile.
+
+use std::{collections::HashMap, fs, io, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Error, GameState,
+    action::{self, Action},
+    drill::{self, Drill},
+    util::format_duration,
+};
+
+/// Where the hands are kept, relative to the working directory.
+pub const STRUGGLES_PATH: &str = "saves/struggles.json";
+
+/// How many of the hardest hands practice picks from.
+const WORST: usize = 5;
+
+/// How much a trade-in counts against a hand, as time spent stuck.
+const DUMP_COST: Duration = Duration::from_secs(30);
+
+/// How a starting hand went.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Struggle {
+    pub rack: Vec<char>,
+    /// The name of the dictionary it was played with.
+    pub dictionary: String,
+    /// How long it took to first peel, or to the end of the game if it never did.
+    pub time_to_peel: Duration,
+    /// How many tiles were traded in before the first peel.
+    pub dumps: usize,
+}
+
+impl Struggle {
+    /// How a finished game's starting hand went, unless it was dealt from a drill
+    /// or resumed from a save that didn't keep it.
+    pub fn of(state: &GameState) -> Option<Self> {
+        if state.drill.is_some() || state.starting_hand.is_empty() {
+            return None;
+        }
+        let peel: usize = state
+            .actions
+            .iter()
+            .position(|x: &Action| *x == Action::Peel)
+            .unwrap_or(state.actions.len());
+        Some(Self {
+            rack: state.starting_hand.clone(),
+            dictionary: state.dictionary.name.clone(),
+            time_to_peel: state
+                .action_times
+                .get(peel)
+                .copied()
+                .unwrap_or_else(|| state.elapsed()),
+            dumps: state.actions[..peel]
+                .iter()
+                .filter(|x: &&Action| matches!(x, Action::Dump(..)))
+                .count(),
+        })
+    }
+
+    /// How much trouble the hand was, counting trade-ins as time.
+    pub fn cost(&self) -> Duration {
+        self.time_to_peel + DUMP_COST * u32::try_from(self.dumps).unwrap_or(u32::MAX)
+    }
+}
+
+/// How often a drill has been cleared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempts {
+    /// How many times the drill was played to the end.
+    pub tries: usize,
+    /// How many of those played out every tile.
+    pub cleared: usize,
+}
+
+impl Attempts {
+    /// The share of tries that cleared the drill, as a whole percentage.
+    pub fn rate(self) -> usize {
+        (self.cleared * 100).checked_div(self.tries).unwrap_or(0)
+    }
+}
+
+/// Every starting hand noted so far.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Struggles {
+    hands: Vec<Struggle>,
+    /// How each drill has gone, by its rack.
+    #[serde(default)]
+    drills: HashMap<String, Attempts>,
+}
+
+impl Struggles {
+    /// Reads the hands kept at `path`. It's fine for there not to be any yet.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let fail = |exception: String| Error::PracticeFailed(exception);
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|x| fail(x.to_string())),
+            Err(exception) if exception.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(exception) => Err(fail(exception.to_string())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let fail = |exception: String| Error::PracticeFailed(exception);
+        let json: String = serde_json::to_string(self).map_err(|x| fail(x.to_string()))?;
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(path, json))
+            .map_err(|x| fail(x.to_string()))
+    }
+
+    /// How a drill has gone so far.
+    pub fn attempts(&self, drill: &Drill) -> Attempts {
+        self.drills
+            .get(&String::from_iter(&drill.rack))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The hardest hands, as drills, worst first.
+    pub fn worst(&self) -> Vec<Drill> {
+        let mut hands: Vec<&Struggle> = self.hands.iter().collect();
+        hands.sort_by_key(|x: &&Struggle| std::cmp::Reverse(x.cost()));
+        hands
+            .into_iter()
+            .take(WORST)
+            .map(|x: &Struggle| Drill {
+                name: format!(
+                    "Your {} hand: {} to peel, {} trade-ins",
+                    x.dictionary,
+                    format_duration(x.time_to_peel),
+                    x.dumps
+                ),
+                rack: x.rack.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Notes how a finished game's starting hand went in the hands kept at `path`, or how
+/// its drill went if it was one.
+///
+/// Returns how the drill has gone now, counting this game.
+pub fn record(state: &GameState, path: &Path) -> Result<Option<Attempts>, Error> {
+    let struggle: Option<Struggle> = Struggle::of(state);
+    if struggle.is_none() && state.drill.is_none() {
+        return Ok(None);
+    }
+    let mut struggles: Struggles = Struggles::load(path)?;
+    struggles.hands.extend(struggle);
+    let attempts: Option<Attempts> = state.drill.as_ref().map(|drill: &Drill| {
+        let attempts: &mut Attempts = struggles
+            .drills
+            .entry(String::from_iter(&drill.rack))
+            .or_default();
+        attempts.tries += 1;
+        attempts.cleared +=
+            usize::from(state.hand.is_empty() && action::validate_board(state).is_ok());
+        *attempts
+    });
+    struggles.save(path)?;
+    Ok(attempts)
+}
+
+/// Picks one of the hardest hands kept at `path` to practice, the same one every time
+/// for the same seed.
+pub fn pick(path: &Path, seed: u64) -> Result<Drill, Error> {
+    let worst: Vec<Drill> = Struggles::load(path)?.worst();
+    if worst.is_empty() {
+        return Err(Error::PracticeFailed(
+            "there aren't any hands to practice yet, so finish a game first".to_string(),
+        ));
+    }
+    Ok(drill::pick(&worst, seed))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, time::Duration};
+
+    use super::{Attempts, Struggle, Struggles, record};
+    use crate::{
+        GameState,
+        action::{self, Action},
+        config::Config,
+        dictionary::{Dictionary, Normalization},
+        drill::Drill,
+        grid::Coordinate,
+    };
+
+    #[test]
+    fn test_struggles() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 7);
+        let rack: Vec<char> = state.hand.tiles().to_vec();
+        state.start();
+        let letter: char = state.hand.tiles()[0];
+        action::apply(&mut state, Action::Dump(letter)).unwrap();
+
+        let struggle: Struggle = Struggle::of(&state).unwrap();
+        assert_eq!(struggle.rack, rack);
+        assert_eq!(struggle.dumps, 1);
+        assert_eq!(
+            struggle.cost(),
+            struggle.time_to_peel + Duration::from_secs(30)
+        );
+
+        // The worst hands come first.
+        let easy: Struggle = Struggle {
+            dumps: 0,
+            time_to_peel: Duration::from_secs(5),
+            ..struggle.clone()
+        };
+        let hard: Struggle = Struggle {
+            dumps: 3,
+            ..struggle
+        };
+        let struggles: Struggles = Struggles {
+            hands: vec![easy, hard],
+            ..Struggles::default()
+        };
+        let worst = struggles.worst();
+        assert!(worst[0].name.ends_with("3 trade-ins"));
+        assert_eq!(worst[0].rack, rack);
+    }
+
+    #[test]
+    fn test_drill_attempts() {
+        let path: PathBuf = std::env::temp_dir().join("guavagrams-drill-attempts.json");
+        let _ = fs::remove_file(&path);
+        let drill: Drill = Drill {
+            name: "Cat".to_string(),
+            rack: "cat".chars().collect(),
+        };
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 7);
+        state.drill = Some(drill.clone());
+        state.deal();
+        state.start();
+
+        // Nothing's been played, so it isn't cleared, and it isn't kept as a starting hand.
+        let attempts: Attempts = record(&state, &path).unwrap().unwrap();
+        assert_eq!(
+            attempts,
+            Attempts {
+                tries: 1,
+                cleared: 0
+            }
+        );
+        assert_eq!(attempts.rate(), 0);
+
+        state.dictionary = Dictionary::new(["cat".to_string()], Normalization::default());
+        for (x, letter) in (0..).zip("cat".chars()) {
+            action::apply(&mut state, Action::Place(letter, Coordinate(x, 0))).unwrap();
+        }
+        assert!(state.hand.is_empty());
+        assert_eq!(record(&state, &path).unwrap().unwrap().rate(), 50);
+
+        let struggles: Struggles = Struggles::load(&path).unwrap();
+        assert!(struggles.hands.is_empty());
+        assert_eq!(struggles.attempts(&drill).cleared, 1);
+        fs::remove_file(&path).unwrap();
+    }
+}
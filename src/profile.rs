@@ -0,0 +1,104 @@
+//! Logs how long each frame took to draw, part by part, for `--profile-render`.
+//!
+//! Every frame is a CSV row of microseconds, so a new overlay that slows the
+//! board down shows up as a column creeping up between two runs. The board
+//! scans and health checks made since the last frame are counted too, since
+//! anything that rescans the board on every move pays for it there.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{Error, GameState};
+
+/// The columns of the log, in order.
+const HEADER: &str = "frame,total_us,panel_us,hand_us,board_us,overlays_us,scan_us,check_us,tiles";
+
+/// How long each part of the last frame took to draw.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderCosts {
+    /// The title and the panel of game info and keys.
+    pub panel: Duration,
+    /// The hand, or the high scores in its place.
+    pub hand: Duration,
+    /// The board, or the distribution preview before the game starts.
+    pub board: Duration,
+    /// The debug overlay, legality report and pause menu.
+    pub overlays: Duration,
+}
+
+/// Writes a row for every frame drawn.
+pub struct RenderProfile<W: Write> {
+    output: W,
+    /// How many frames have been written.
+    frames: u64,
+}
+
+impl RenderProfile<BufWriter<File>> {
+    /// Starts a log in a file, replacing anything already there.
+    pub fn create(path: &Path) -> Result<Self, Error> {
+        let file: File = File::create(path).map_err(|x| Error::ProfileFailed(x.to_string()))?;
+        Self::new(BufWriter::new(file))
+    }
+}
+
+impl<W: Write> RenderProfile<W> {
+    /// Starts a log by writing the header.
+    pub fn new(mut output: W) -> Result<Self, Error> {
+        writeln!(output, "{HEADER}").map_err(|x| Error::ProfileFailed(x.to_string()))?;
+        Ok(Self { output, frames: 0 })
+    }
+
+    /// Writes the costs of the frame just drawn.
+    pub fn frame(&mut self, state: &GameState) -> Result<(), Error> {
+        let micros = |x: Duration| -> u128 { x.as_micros() };
+        let costs: RenderCosts = state.metrics.render;
+        writeln!(
+            self.output,
+            "{},{},{},{},{},{},{},{},{}",
+            self.frames,
+            micros(state.metrics.frame_time),
+            micros(costs.panel),
+            micros(costs.hand),
+            micros(costs.board),
+            micros(costs.overlays),
+            micros(state.camera.scan_time),
+            micros(state.camera.check_time),
+            state.camera.stats.tiles
+        )
+        .map_err(|x| Error::ProfileFailed(x.to_string()))?;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{HEADER, RenderProfile};
+    use crate::{GameState, config::Config, dictionary::Dictionary};
+
+    #[test]
+    fn test_profile_rows() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 0);
+        state.metrics.frame_time = Duration::from_micros(1500);
+        state.metrics.render.board = Duration::from_micros(900);
+        state.camera.scan_time = Duration::from_micros(40);
+
+        let mut output: Vec<u8> = Vec::new();
+        let mut profile: RenderProfile<&mut Vec<u8>> = RenderProfile::new(&mut output).unwrap();
+        profile.frame(&state).unwrap();
+        profile.frame(&state).unwrap();
+
+        let output: String = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            [HEADER, "0,1500,0,0,900,0,40,0,0", "1,1500,0,0,900,0,40,0,0"]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Records a whole session as an asciicast (v2) file, which asciinema and its
+//! web player can play back.
+//!
+//! Every frame that's different from the last is written out in full, with
+//! how long into the session it was drawn.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ratatui::buffer::Buffer;
+
+use crate::{Error, screenshot::ansi_lines};
+
+/// Writes frames to an asciicast as they're drawn.
+pub struct Recorder<W: Write> {
+    output: W,
+    start: Instant,
+    /// The last frame written, so unchanged frames can be skipped.
+    last: Option<Buffer>,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// Starts recording to a file, replacing anything already there.
+    pub fn create(path: &Path, width: u16, height: u16) -> Result<Self, Error> {
+        let file: File = File::create(path).map_err(|x| Error::RecordingFailed(x.to_string()))?;
+        Self::new(BufWriter::new(file), width, height)
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Starts recording by writing the asciicast header.
+    pub fn new(mut output: W, width: u16, height: u16) -> Result<Self, Error> {
+        let timestamp: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs());
+        writeln!(
+            output,
+            "{{\"version\": 2, \"width\": {width}, \"height\": {height}, \"timestamp\": {timestamp}}}"
+        )
+        .map_err(|x| Error::RecordingFailed(x.to_string()))?;
+        Ok(Self {
+            output,
+            start: Instant::now(),
+            last: None,
+        })
+    }
+
+    /// Records a frame, unless it's the same as the last one.
+    pub fn frame(&mut self, buffer: &Buffer) -> Result<(), Error> {
+        self.frame_at(buffer, self.start.elapsed())
+    }
+
+    fn frame_at(&mut self, buffer: &Buffer, time: Duration) -> Result<(), Error> {
+        if self.last.as_ref() == Some(buffer) {
+            return Ok(());
+        }
+        // Go back to the top left and redraw the whole screen.
+        let data: String = format!("\x1b[H{}", ansi_lines(buffer).join("\r\n"));
+        writeln!(
+            self.output,
+            "[{:.6}, \"o\", \"{}\"]",
+            time.as_secs_f64(),
+            escape_json(&data)
+        )
+        .and_then(|()| self.output.flush())
+        .map_err(|x| Error::RecordingFailed(x.to_string()))?;
+        self.last = Some(buffer.clone());
+        Ok(())
+    }
+}
+
+/// Escapes text for use inside a JSON string.
+fn escape_json(text: &str) -> String {
+    let mut output: String = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            x if x.is_control() => write!(&mut output, "\\u{:04x}", u32::from(x)).unwrap(),
+            x => output.push(x),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+    use super::{Recorder, escape_json};
+
+    #[test]
+    fn test_recording() {
+        assert_eq!(escape_json("\"a\"\\\x1b"), "\\\"a\\\"\\\\\\u001b");
+
+        let mut buffer: Buffer = Buffer::empty(Rect::new(0, 0, 2, 2));
+        buffer.set_string(0, 0, "hi", Style::new());
+
+        let mut output: Vec<u8> = Vec::new();
+        let mut recorder: Recorder<&mut Vec<u8>> = Recorder::new(&mut output, 2, 2).unwrap();
+        recorder
+            .frame_at(&buffer, Duration::from_millis(1500))
+            .unwrap();
+        // An unchanged frame isn't written again.
+        recorder.frame_at(&buffer, Duration::from_secs(2)).unwrap();
+
+        let output: String = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("{\"version\": 2, \"width\": 2, \"height\": 2, "));
+        assert_eq!(
+            lines[1],
+            "[1.500000, \"o\", \"\\u001b[H\\u001b[0mhi\\u001b[0m\\r\\n\\u001b[0m  \\u001b[0m\"]"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Stepping through an imported game, or letting it play itself back.
+//!
+//! Exported games carry how far into the game each move was made, so a replay
+//! plays at the pace the game was played. Long thinks are cut short, to keep
+//! the good bits moving, and games exported before moves were timed play back
+//! a move a second.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Error, EventResponse, GamePhase, GameState,
+    action::{self, Action},
+};
+
+/// The speeds a replay can play back at.
+const SPEEDS: [f64; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// The longest a replay waits between two moves.
+const LONGEST_GAP: Duration = Duration::from_secs(3);
+
+/// How long a replay waits between moves that weren't timed.
+const UNTIMED_GAP: Duration = Duration::from_secs(1);
+
+/// When each of a replay's moves comes up, given when they were first made.
+///
+/// Unless every move has a time, none of them are used.
+pub fn pace(times: &[Duration], moves: usize) -> VecDeque<Duration> {
+    let complete: bool = times.len() == moves;
+    let mut clock: Duration = Duration::ZERO;
+    let mut last: Duration = Duration::ZERO;
+    (0..moves)
+        .map(|index: usize| {
+            clock += if complete {
+                let gap: Duration = times[index].saturating_sub(last);
+                last = times[index];
+                gap.min(LONGEST_GAP)
+            } else {
+                UNTIMED_GAP
+            };
+            clock
+        })
+        .collect()
+}
+
+/// A replay playing itself back.
+#[derive(Debug, Clone, Copy)]
+pub struct Autoplay {
+    /// Which of `SPEEDS` it's playing at.
+    speed: usize,
+    /// How far into the replay it's got.
+    position: Duration,
+    /// When the position last moved on, or `None` while it's stopped.
+    ticked: Option<Instant>,
+}
+
+impl Default for Autoplay {
+    fn default() -> Self {
+        Self {
+            speed: 1,
+            position: Duration::ZERO,
+            ticked: None,
+        }
+    }
+}
+
+impl Autoplay {
+    pub const fn is_playing(&self) -> bool {
+        self.ticked.is_some()
+    }
+
+    pub const fn speed(&self) -> f64 {
+        SPEEDS[self.speed]
+    }
+
+    /// Moves on by however long it's been playing since the last time, and says how far in it is.
+    pub fn advance(&mut self, now: Instant) -> Duration {
+        if let Some(ticked) = self.ticked {
+            self.position += now.duration_since(ticked).mul_f64(self.speed());
+            self.ticked = Some(now);
+        }
+        self.position
+    }
+
+    /// Keeps the position where it is, for time spent paused.
+    pub const fn hold(&mut self, now: Instant) {
+        if self.ticked.is_some() {
+            self.ticked = Some(now);
+        }
+    }
+
+    /// Starts it playing, or stops it.
+    pub fn toggle(&mut self, now: Instant) {
+        self.advance(now);
+        self.ticked = match self.ticked {
+            Some(_) => None,
+            None => Some(now),
+        };
+    }
+
+    pub fn faster(&mut self, now: Instant) {
+        self.advance(now);
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self, now: Instant) {
+        self.advance(now);
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// Jumps ahead to a move made by hand, so playing on carries on from there.
+    fn skip_to(&mut self, position: Duration) {
+        self.position = self.position.max(position);
+    }
+}
+
+/// Makes the next imported move, with the cursor following it around the board.
+pub fn step(state: &mut GameState) -> Result<EventResponse, Error> {
+    let Some(action) = state.playback.pop_front() else {
+        return Ok(EventResponse::Pass);
+    };
+    if let Some(time) = state.playback_times.pop_front() {
+        state.autoplay.skip_to(time);
+    }
+    if let Action::Place(_, coordinate)
+    | Action::PickUp(coordinate)
+    | Action::PickUpRun(coordinate, _) = action
+    {
+        state.camera.cursor = coordinate;
+    }
+    let response: Result<EventResponse, Error> = action::apply(state, action);
+    hand_over_if_done(state)?;
+    response
+}
+
+/// Makes every imported move that's left.
+pub fn step_all(state: &mut GameState) -> Result<EventResponse, Error> {
+    // Failed peels are part of the record, so keep going past them.
+    let mut response: Result<EventResponse, Error> = Ok(EventResponse::Pass);
+    while !state.playback.is_empty() {
+        response = step(state);
+    }
+    response
+}
+
+/// Makes every imported move that's come up, while the replay is playing itself.
+pub fn play_due(state: &mut GameState) -> Result<EventResponse, Error> {
+    if state.phase != GamePhase::Reviewing {
+        return Ok(EventResponse::Pass);
+    }
+    if state.paused_since.is_some() {
+        state.autoplay.hold(Instant::now());
+        return Ok(EventResponse::Pass);
+    }
+    let position: Duration = state.autoplay.advance(Instant::now());
+    let mut response: Result<EventResponse, Error> = Ok(EventResponse::Pass);
+    while state
+        .playback_times
+        .front()
+        .is_some_and(|time: &Duration| *time <= position)
+    {
+        response = step(state);
+    }
+    response
+}
+
+/// Hands the game over once every imported move has been made, as long as it ended
+/// up where the original game did.
+fn hand_over_if_done(state: &mut GameState) -> Result<(), Error> {
+    if state.playback.is_empty() && state.phase == GamePhase::Reviewing {
+        state.phase = GamePhase::Playing;
+        if let Some(expected) = state.playback_checksum.take()
+            && expected != state.camera.stats.checksum
+        {
+            return Err(Error::ReplayDiverged(expected, state.camera.stats.checksum));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Autoplay, pace};
+
+    #[test]
+    fn test_pace() {
+        let seconds =
+            |x: &[u64]| -> Vec<Duration> { x.iter().copied().map(Duration::from_secs).collect() };
+        // A long think is cut down to three seconds.
+        assert_eq!(pace(&seconds(&[1, 2, 60, 61]), 4), seconds(&[1, 2, 5, 6]));
+        // Without a time for every move, it's a move a second.
+        assert_eq!(pace(&seconds(&[1]), 3), seconds(&[1, 2, 3]));
+
+        let start: Instant = Instant::now();
+        let mut autoplay: Autoplay = Autoplay::default();
+        assert_eq!(
+            autoplay.advance(start + Duration::from_secs(5)),
+            Duration::ZERO
+        );
+        autoplay.toggle(start);
+        autoplay.faster(start);
+        assert_eq!(
+            autoplay.advance(start + Duration::from_secs(2)),
+            Duration::from_secs(4)
+        );
+        autoplay.toggle(start + Duration::from_secs(2));
+        assert!(!autoplay.is_playing());
+        assert_eq!(
+            autoplay.advance(start + Duration::from_secs(9)),
+            Duration::from_secs(4)
+        );
+    }
+}
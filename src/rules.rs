@@ -0,0 +1,112 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, grid::Word};
+
+/// An optional change to the rules, picked before the game starts.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Mutator {
+    /// A letter is left out of the pile entirely.
+    NoLetter(char),
+    /// Every word has to be at least this long.
+    MinimumLength(usize),
+    /// Words containing a letter are worth more.
+    BonusLetter(char, f64),
+    /// The game ends as soon as the pile can't give every player a tile, and
+    /// whatever's left in the hand counts against the score.
+    DumpRemaining,
+    /// Calling the last peel with a bad board loses every point, instead of 5%.
+    RottenBanana,
+}
+
+/// Every mutator that can be picked at the start of a game, in order.
+pub const MUTATORS: [Mutator; 5] = [
+    Mutator::NoLetter('s'),
+    Mutator::MinimumLength(4),
+    Mutator::BonusLetter('q', 2.0),
+    Mutator::DumpRemaining,
+    Mutator::RottenBanana,
+];
+
+impl Display for Mutator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoLetter(letter) => write!(f, "No '{letter}' tiles"),
+            Self::MinimumLength(length) => write!(f, "All words at least {length} letters"),
+            Self::BonusLetter(letter, multiplier) => {
+                write!(f, "{multiplier}x points for words with '{letter}'")
+            }
+            Self::DumpRemaining => write!(f, "Leftover tiles cost points at the endgame"),
+            Self::RottenBanana => write!(f, "A bad final peel loses everything"),
+        }
+    }
+}
+
+/// The mutators in play for a game.
+///
+/// Pile creation, validation and scoring all ask the rules, so mutators can
+/// be combined freely.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Rules {
+    pub mutators: Vec<Mutator>,
+}
+
+impl Rules {
+    /// Turns a mutator on if it's off, or off if it's on.
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if let Some(position) = self.mutators.iter().position(|x| *x == mutator) {
+            self.mutators.remove(position);
+        } else {
+            self.mutators.push(mutator);
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.mutators.contains(&mutator)
+    }
+
+    /// Checks if a letter can go into the pile.
+    #[must_use]
+    pub fn allows_tile(&self, tile: char) -> bool {
+        self.mutators
+            .iter()
+            .all(|mutator: &Mutator| !matches!(mutator, Mutator::NoLetter(x) if *x == tile))
+    }
+
+    /// Checks the words on a board against every mutator.
+    ///
+    /// # Errors
+    ///
+    /// `Error::WordTooShort` for the first word shorter than a `MinimumLength` allows.
+    pub fn validate_words(&self, words: &[Word]) -> Result<(), Error> {
+        for mutator in &self.mutators {
+            if let Mutator::MinimumLength(length) = mutator
+                && let Some(word) = words
+                    .iter()
+                    .find(|word: &&Word| word.text.chars().count() < *length)
+            {
+                return Err(Error::WordTooShort(
+                    word.text.clone(),
+                    *length,
+                    word.cells().collect(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies every mutator's bonus to a word's score.
+    #[must_use]
+    pub fn score_word(&self, word: &str, score: f64) -> f64 {
+        self.mutators
+            .iter()
+            .fold(score, |score: f64, mutator: &Mutator| match mutator {
+                Mutator::BonusLetter(letter, multiplier) if word.contains(*letter) => {
+                    score * multiplier
+                }
+                _ => score,
+            })
+    }
+}
@@ -0,0 +1,524 @@
+//! Saving a game part way through, to pick it up again later.
+//!
+//! Unlike an exported game, which only keeps the seed and the moves, a save
+//! keeps the board, hand, pile and random state exactly as they were, along
+//! with the time played so far. Only the dictionary's name is saved, so a game
+//! resumes with whichever one is picked at startup.
+//!
+//! Saves go in named slots, one file each, which can be looked through, renamed
+//! and deleted on the screen `--resume` opens.
+
+use std::{
+    cmp::Reverse,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use rand_chacha::ChaCha12Rng;
+use ratatui::{
+    DefaultTerminal, Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Styled},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Error, GamePhase, GameState,
+    action::Action,
+    analysis::Difficulty,
+    bot::Bot,
+    camera::{Camera, Theme},
+    challenge::Challenge,
+    dictionary::Distribution,
+    drill::Drill,
+    grid::{Coordinate, Grid},
+    rules::Rules,
+    tiles::{Hand, Pile},
+    util::format_duration,
+};
+
+/// The folder save slots are kept in, relative to the working directory.
+pub const SAVE_DIRECTORY: &str = "saves/slots";
+
+/// How many cells across and down a board's thumbnail shows, at most.
+const THUMBNAIL_SIZE: (i32, i32) = (24, 12);
+
+/// Everything needed to carry on with a game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Save {
+    seed: u64,
+    /// The name of the dictionary the game was played with.
+    #[serde(default)]
+    dictionary: String,
+    distribution: Distribution,
+    pile_size: usize,
+    hand_size: usize,
+    rules: Rules,
+    /// The week of the challenge being played, if this game is one.
+    challenge: Option<u64>,
+    drill: Option<Drill>,
+    casual: bool,
+    endless: bool,
+    time_limit: Option<Duration>,
+    target_score: Option<i64>,
+    difficulty: Option<Difficulty>,
+    bot: Option<Bot>,
+    grid: Grid<Option<char>>,
+    pile: Pile,
+    hand: Hand,
+    /// Empty for saves from before starting hands were kept.
+    #[serde(default)]
+    starting_hand: Vec<char>,
+    rng: ChaCha12Rng,
+    score: i64,
+    elapsed: Duration,
+    actions: Vec<Action>,
+    #[serde(default)]
+    action_times: Vec<Duration>,
+}
+
+impl Save {
+    pub fn of(state: &GameState) -> Self {
+        Self {
+            seed: state.seed,
+            dictionary: state.dictionary.name.clone(),
+            distribution: state.distribution.clone(),
+            pile_size: state.pile_size,
+            hand_size: state.hand_size,
+            rules: state.rules.clone(),
+            challenge: state.challenge.as_ref().map(|x: &Challenge| x.week),
+            drill: state.drill.clone(),
+            casual: state.casual,
+            endless: state.endless,
+            time_limit: state.time_limit,
+            target_score: state.target_score,
+            difficulty: state.difficulty,
+            bot: state.bot.clone(),
+            grid: state.camera.grid.clone(),
+            pile: state.pile.clone(),
+            hand: state.hand.clone(),
+            starting_hand: state.starting_hand.clone(),
+            rng: state.rng.clone(),
+            score: state.score,
+            elapsed: state.elapsed(),
+            actions: state.actions.clone(),
+            action_times: state.action_times.clone(),
+        }
+    }
+
+    /// The board as it was saved.
+    pub fn into_board(self) -> Grid<Option<char>> {
+        self.grid
+    }
+
+    /// The board as text, cropped to its top-left corner if it's too big, with a dot for each empty cell.
+    pub fn thumbnail(&self) -> Vec<String> {
+        let Some((min, max)) = self.grid.bounding_box() else {
+            return Vec::new();
+        };
+        let (width, height): (i32, i32) = THUMBNAIL_SIZE;
+        let region: (Coordinate, Coordinate) = (
+            Coordinate(min.0, min.1.max(max.1 - height + 1)),
+            Coordinate(max.0.min(min.0 + width - 1), max.1),
+        );
+        self.grid
+            .rows_in(region)
+            .map(|row| row.map(|(.., tile)| tile.unwrap_or('·')).collect())
+            .collect()
+    }
+
+    /// Puts a saved game into a state, ready to play.
+    pub fn restore(self, state: &mut GameState) {
+        state.seed = self.seed;
+        state.distribution = self.distribution;
+        state.pile_size = self.pile_size;
+        state.hand_size = self.hand_size;
+        state.rules = self.rules;
+        state.pick_hot_letter();
+        state.challenge = self.challenge.map(Challenge::for_week);
+        state.drill = self.drill;
+        state.casual = self.casual;
+        state.endless = self.endless;
+        state.time_limit = self.time_limit;
+        state.target_score = self.target_score;
+        state.difficulty = self.difficulty;
+        state.players = 1 + usize::from(self.bot.is_some());
+        state.bot = self.bot;
+        // Swap in the saved board, but keep how it's drawn.
+        let (theme, assist): (Theme, bool) = (state.camera.theme, state.camera.assist);
+        state.camera = Camera::new(self.grid);
+        state.camera.theme = theme;
+        state.camera.assist = assist;
+        state.camera.check_health(&state.dictionary, &state.rules);
+        state.pile = self.pile;
+        state.hand = self.hand;
+        state.starting_hand = self.starting_hand;
+        state.rng = self.rng;
+        state.score = self.score;
+        state.actions = self.actions;
+        // Saves from before moves were timed count them as made at the start.
+        state.action_times = self.action_times;
+        state
+            .action_times
+            .resize(state.actions.len(), Duration::ZERO);
+
+        state.start();
+        // Backdate the start so the clock carries on from where it was.
+        state.game_start = Instant::now()
+            .checked_sub(self.elapsed)
+            .unwrap_or(state.game_start);
+        state.phase = GamePhase::Playing;
+    }
+}
+
+/// Writes a game to the save file.
+pub fn save(state: &GameState, path: &Path) -> Result<(), Error> {
+    let json: String = serde_json::to_string(&Save::of(state))
+        .map_err(|exception| Error::SaveFailed(exception.to_string()))?;
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(path, json))
+        .map_err(|exception| Error::SaveFailed(exception.to_string()))
+}
+
+/// Reads a game back from a save file.
+pub fn load(path: &Path) -> Result<Save, Error> {
+    let json: String =
+        fs::read_to_string(path).map_err(|exception| Error::ResumeFailed(exception.to_string()))?;
+    serde_json::from_str(&json).map_err(|exception| Error::ResumeFailed(exception.to_string()))
+}
+
+/// Where the save slot called `name` is kept in a folder.
+pub fn slot_path(directory: &Path, name: &str) -> PathBuf {
+    directory.join(format!("{name}.json"))
+}
+
+/// A save slot, and the game in it, if it could be read.
+pub struct Slot {
+    pub name: String,
+    pub save: Result<Save, Error>,
+}
+
+/// Every save slot in a folder, the most recently saved first. It's fine for there not to be a folder yet.
+pub fn list_slots(directory: &Path) -> Vec<Slot> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut slots: Vec<(SystemTime, Slot)> = entries
+        .filter_map(Result::ok)
+        .map(|entry: fs::DirEntry| entry.path())
+        .filter(|path: &PathBuf| path.extension().is_some_and(|x| x == "json"))
+        .filter_map(|path: PathBuf| {
+            let modified: SystemTime = fs::metadata(&path)
+                .and_then(|x: fs::Metadata| x.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            let name: String = path.file_stem()?.to_string_lossy().into_owned();
+            Some((
+                modified,
+                Slot {
+                    name,
+                    save: load(&path),
+                },
+            ))
+        })
+        .collect();
+    slots.sort_by_key(|(modified, slot)| (Reverse(*modified), slot.name.clone()));
+    slots.into_iter().map(|(.., slot)| slot).collect()
+}
+
+/// Checks that a slot name can be a file name, without going into other folders.
+fn check_slot_name(name: &str) -> Result<(), Error> {
+    if name.trim().is_empty()
+        || name.starts_with('.')
+        || name.contains(['/', '\\', ':'])
+        || name.chars().any(char::is_control)
+    {
+        return Err(Error::SaveFailed(format!(
+            "\"{name}\" can't be the name of a save slot."
+        )));
+    }
+    Ok(())
+}
+
+/// Renames a save slot, as long as there isn't already one with the new name.
+pub fn rename_slot(directory: &Path, from: &str, to: &str) -> Result<(), Error> {
+    check_slot_name(to)?;
+    let target: PathBuf = slot_path(directory, to);
+    if target.exists() {
+        return Err(Error::SaveFailed(format!(
+            "There's already a save slot called \"{to}\"."
+        )));
+    }
+    fs::rename(slot_path(directory, from), target)
+        .map_err(|exception| Error::SaveFailed(exception.to_string()))
+}
+
+pub fn delete_slot(directory: &Path, name: &str) -> Result<(), Error> {
+    fs::remove_file(slot_path(directory, name))
+        .map_err(|exception| Error::SaveFailed(exception.to_string()))
+}
+
+/// What the save slot screen is showing.
+struct SlotBrowser<'a> {
+    directory: &'a Path,
+    slots: Vec<Slot>,
+    list_state: ListState,
+    /// The new name being typed for the highlighted slot, if one is.
+    renaming: Option<String>,
+    /// Whether the next Shift + D deletes the highlighted slot.
+    confirming_delete: bool,
+    status: Span<'static>,
+}
+
+impl SlotBrowser<'_> {
+    /// Reads the slots again, after one's been changed.
+    fn refresh(&mut self) {
+        self.slots = list_slots(self.directory);
+        if self.slots.is_empty() {
+            self.list_state.select(None);
+        } else if self
+            .list_state
+            .selected()
+            .is_none_or(|x: usize| x >= self.slots.len())
+        {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    fn selected(&self) -> Option<&Slot> {
+        self.slots.get(self.list_state.selected()?)
+    }
+
+    /// Shows how changing a slot went, and reads the slots again.
+    fn report(&mut self, result: Result<(), Error>, done: String) {
+        self.status = match result {
+            Ok(()) => done.set_style(Style::new().fg(Color::Green)),
+            Err(exception) => exception.to_string().set_style(Style::new().fg(Color::Red)),
+        };
+        self.refresh();
+    }
+}
+
+/// Shows every save slot, to rename or delete them or pick one to carry on with.
+///
+/// Returns the picked slot's name and save, or nothing if the player quits.
+pub fn browse(
+    terminal: &mut DefaultTerminal,
+    directory: &Path,
+) -> io::Result<Option<(String, Save)>> {
+    let mut browser: SlotBrowser = SlotBrowser {
+        directory,
+        slots: Vec::new(),
+        list_state: ListState::default(),
+        renaming: None,
+        confirming_delete: false,
+        status: Span::default(),
+    };
+    browser.refresh();
+
+    loop {
+        terminal.draw(|frame: &mut Frame| draw(frame, &mut browser))?;
+        let Event::Key(event) = event::read()? else {
+            continue;
+        };
+        if event.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if let Some(renaming) = &mut browser.renaming {
+            match event.code {
+                KeyCode::Char(letter) => renaming.push(letter),
+                KeyCode::Backspace => {
+                    renaming.pop();
+                }
+                KeyCode::Enter => {
+                    let to: String = std::mem::take(renaming);
+                    browser.renaming = None;
+                    if let Some(from) = browser.selected().map(|x: &Slot| x.name.clone()) {
+                        let result: Result<(), Error> = rename_slot(directory, &from, &to);
+                        browser.report(result, format!("Renamed \"{from}\" to \"{to}\"."));
+                    }
+                }
+                KeyCode::Esc => browser.renaming = None,
+                _ => (),
+            }
+            continue;
+        }
+        let confirmed: bool = std::mem::take(&mut browser.confirming_delete);
+        match event.code {
+            KeyCode::Up => browser.list_state.select_previous(),
+            KeyCode::Down => browser.list_state.select_next(),
+            KeyCode::Enter => {
+                let Some(index) = browser.list_state.selected() else {
+                    continue;
+                };
+                let slot: Slot = browser
+                    .slots
+                    .swap_remove(index.min(browser.slots.len() - 1));
+                match slot.save {
+                    Ok(save) => return Ok(Some((slot.name, save))),
+                    Err(exception) => browser.report(Err(exception), String::new()),
+                }
+            }
+            KeyCode::Char('R') if browser.selected().is_some() => {
+                browser.renaming = Some(String::new());
+            }
+            KeyCode::Char('D') => {
+                let Some(name) = browser.selected().map(|x: &Slot| x.name.clone()) else {
+                    continue;
+                };
+                if confirmed {
+                    let result: Result<(), Error> = delete_slot(directory, &name);
+                    browser.report(result, format!("Deleted \"{name}\"."));
+                } else {
+                    browser.confirming_delete = true;
+                    browser.status = format!("Press Shift + D again to delete \"{name}\".")
+                        .set_style(Style::new().fg(Color::Yellow));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('Q') => return Ok(None),
+            _ => (),
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, browser: &mut SlotBrowser) {
+    let [body, footer]: [Rect; 2] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+    let [list, details]: [Rect; 2] =
+        Layout::horizontal([Constraint::Percentage(30), Constraint::Fill(1)]).areas(body);
+
+    let items: Vec<ListItem> = browser
+        .slots
+        .iter()
+        .map(|slot: &Slot| ListItem::new(slot.name.as_str()))
+        .collect();
+    frame.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ")
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Save Slots ({})", browser.slots.len())),
+            ),
+        list,
+        &mut browser.list_state,
+    );
+
+    let lines: Vec<Line> = match browser.selected().map(|x: &Slot| &x.save) {
+        Some(Ok(save)) => {
+            let mut lines: Vec<Line> = vec![
+                Line::raw(format!("Score: {}", save.score)),
+                Line::raw(format!("Time: {}", format_duration(save.elapsed))),
+                Line::raw(format!(
+                    "Dictionary: {}",
+                    if save.dictionary.is_empty() {
+                        "unknown"
+                    } else {
+                        &save.dictionary
+                    }
+                )),
+                Line::raw(format!("Seed: {}", save.seed)),
+                Line::default(),
+            ];
+            lines.extend(save.thumbnail().into_iter().map(Line::raw));
+            lines
+        }
+        Some(Err(exception)) => vec![Line::styled(
+            exception.to_string(),
+            Style::new().fg(Color::Red),
+        )],
+        None => vec![Line::raw(
+            "No saves yet. Press Shift + S in a game to make one.",
+        )],
+    };
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Save")),
+        details,
+    );
+
+    let line: Line = match &browser.renaming {
+        Some(renaming) => Line::raw(format!("Rename to: {renaming}_")),
+        None if !browser.status.content.is_empty() => Line::from(browser.status.clone()),
+        None => Line::styled(
+            " ↑/↓ Move  Enter Resume  Shift + R Rename  Shift + D Delete  Shift + Q/Esc Quit",
+            Style::new().fg(Color::DarkGray),
+        ),
+    };
+    frame.render_widget(line, footer);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, process};
+
+    use rand::Rng;
+
+    use super::{Save, Slot, delete_slot, list_slots, rename_slot, save, slot_path};
+    use crate::{
+        GameState,
+        action::{self, Action},
+        config::Config,
+        dictionary::Dictionary,
+        grid::Coordinate,
+    };
+
+    #[test]
+    fn test_round_trip() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 7);
+        state.start();
+        let letter: char = state.hand.tiles()[0];
+        action::apply(&mut state, Action::Place(letter, Coordinate(2, -1))).unwrap();
+
+        let json: String = serde_json::to_string(&Save::of(&state)).unwrap();
+        let save: Save = serde_json::from_str(&json).unwrap();
+        let mut resumed: GameState = GameState::new(Dictionary::default(), &Config::default(), 0);
+        save.restore(&mut resumed);
+
+        assert_eq!(resumed.seed, 7);
+        assert_eq!(resumed.camera.grid[Coordinate(2, -1)], Some(letter));
+        assert_eq!(resumed.hand.tiles(), state.hand.tiles());
+        assert_eq!(resumed.pile.tiles(), state.pile.tiles());
+        assert_eq!(resumed.actions, state.actions);
+        // The random state carries over too, so the next draw is the same.
+        assert_eq!(resumed.rng.random::<u64>(), state.rng.random::<u64>());
+    }
+
+    #[test]
+    fn test_slots() {
+        let directory: PathBuf =
+            env::temp_dir().join(format!("guavagrams-slots-{}", process::id()));
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 7);
+        state.start();
+        for (x, letter) in (0..).zip(state.hand.tiles().to_vec().into_iter().take(3)) {
+            action::apply(&mut state, Action::Place(letter, Coordinate(x, 0))).unwrap();
+        }
+        save(&state, &slot_path(&directory, "first")).unwrap();
+        save(&state, &slot_path(&directory, "second")).unwrap();
+
+        let slots: Vec<Slot> = list_slots(&directory);
+        assert_eq!(slots.len(), 2);
+        let save: &Save = slots[0].save.as_ref().unwrap();
+        assert_eq!(save.thumbnail().len(), 1);
+        assert_eq!(save.thumbnail()[0].chars().count(), 3);
+
+        // Names can't go into other folders, or take one that's already used.
+        assert!(rename_slot(&directory, "first", "../escaped").is_err());
+        assert!(rename_slot(&directory, "first", "second").is_err());
+        rename_slot(&directory, "first", "third").unwrap();
+        delete_slot(&directory, "second").unwrap();
+        let names: Vec<String> = list_slots(&directory)
+            .into_iter()
+            .map(|slot: Slot| slot.name)
+            .collect();
+        assert_eq!(names, ["third"]);
+
+        fs::remove_dir_all(&directory).unwrap();
+        assert!(list_slots(&directory).is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+//! A small strip of live game numbers, written to a file for streamers.
+//!
+//! Streaming software can show a text file as an overlay and redraw it when it
+//! changes (OBS's text source can read from a file), so the score can go on
+//! stream without capturing the whole terminal.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{Error, GameState, util::format_duration};
+
+/// How many characters wide the strip is, so the overlay never changes size.
+pub const WIDTH: usize = 40;
+
+/// Keeps the strip's file up to date.
+pub struct ScoreCam {
+    path: PathBuf,
+    /// The last strip written, so unchanged ones aren't written again.
+    last: String,
+}
+
+impl ScoreCam {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            last: String::new(),
+        }
+    }
+
+    /// Rewrites the file if anything on the strip has changed.
+    pub fn update(&mut self, state: &GameState) -> Result<(), Error> {
+        let strip: String = strip(state);
+        if strip == self.last {
+            return Ok(());
+        }
+
+        // Swap the whole file in at once, so the overlay never reads half a strip.
+        let temporary: PathBuf = self.path.with_extension("tmp");
+        fs::write(&temporary, &strip)
+            .and_then(|()| fs::rename(&temporary, &self.path))
+            .map_err(|exception| Error::ScoreCamFailed(exception.to_string()))?;
+        self.last = strip;
+        Ok(())
+    }
+}
+
+/// The score, time played and word count, padded or cut to exactly `WIDTH` characters.
+pub fn strip(state: &GameState) -> String {
+    let text: String = format!(
+        "{} pts | {} | {} words",
+        state.score,
+        format_duration(state.elapsed()),
+        state.camera.stats.words.len()
+    );
+    format!("{text:<WIDTH$.WIDTH$}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WIDTH, strip};
+    use crate::{GameState, config::Config, dictionary::Dictionary};
+
+    #[test]
+    fn test_strip_is_fixed_width() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 0);
+        assert_eq!(
+            strip(&state),
+            format!("{:<WIDTH$}", "0 pts | 00:00:00 | 0 words")
+        );
+
+        state.score = i64::MIN;
+        assert_eq!(strip(&state).chars().count(), WIDTH);
+    }
+}
@@ -0,0 +1,147 @@
+//! The high score table, kept on disk between games.
+
+use std::{fs, io, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, GameState};
+
+/// Where the high scores are kept, relative to the working directory.
+pub const SCORES_PATH: &str = "saves/scores.json";
+
+/// How many scores are shown at the end of a game.
+pub const SHOWN: usize = 10;
+
+/// One finished game on the table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: i64,
+    pub duration: Duration,
+    /// The name of the dictionary the game was played with.
+    pub dictionary: String,
+    /// What kind of game it was, like "blitz" or "weekly".
+    pub mode: String,
+    /// How many hints the game took, so assisted games stand out.
+    #[serde(default)]
+    pub hints: usize,
+}
+
+impl HighScore {
+    pub fn of(state: &GameState) -> Self {
+        Self {
+            score: state.score,
+            duration: state.elapsed(),
+            dictionary: state.dictionary.name.clone(),
+            mode: mode(state).to_string(),
+            hints: state.hints_used(),
+        }
+    }
+}
+
+/// Every recorded game, best first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Puts a game on the table, returning where it placed, counting from 0.
+    ///
+    /// Higher scores go first, then quicker games. A game that ties with an
+    /// older one on both goes after it.
+    pub fn insert(&mut self, entry: HighScore) -> usize {
+        let place: usize = self.scores.partition_point(|x: &HighScore| {
+            x.score > entry.score || (x.score == entry.score && x.duration <= entry.duration)
+        });
+        self.scores.insert(place, entry);
+        place
+    }
+
+    /// The best scores, as many as are shown.
+    pub fn top(&self) -> &[HighScore] {
+        &self.scores[..self.scores.len().min(SHOWN)]
+    }
+}
+
+/// The high scores as they stood after a game, and where it placed.
+#[derive(Debug, Clone)]
+pub struct Standing {
+    pub top: Vec<HighScore>,
+    pub place: usize,
+}
+
+/// Puts a finished game on the table kept at `path`.
+pub fn record(state: &GameState, path: &Path) -> Result<Standing, Error> {
+    let fail = |exception: String| Error::ScoresFailed(exception);
+    let mut scores: HighScores = match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|x| fail(x.to_string()))?,
+        Err(exception) if exception.kind() == io::ErrorKind::NotFound => HighScores::default(),
+        Err(exception) => return Err(fail(exception.to_string())),
+    };
+    let place: usize = scores.insert(HighScore::of(state));
+
+    let json: String = serde_json::to_string(&scores).map_err(|x| fail(x.to_string()))?;
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(path, json))
+        .map_err(|x| fail(x.to_string()))?;
+    Ok(Standing {
+        top: scores.top().to_vec(),
+        place,
+    })
+}
+
+/// What kind of game is being played, for the table.
+fn mode(state: &GameState) -> &'static str {
+    if state.challenge.is_some() {
+        "weekly"
+    } else if state.drill.is_some() {
+        "drill"
+    } else if state.bot.is_some() {
+        "bot"
+    } else if state.target_score.is_some() {
+        "target"
+    } else if state.endless {
+        "endless"
+    } else if state.time_limit.is_some() {
+        "blitz"
+    } else if state.casual {
+        "casual"
+    } else {
+        "normal"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{HighScore, HighScores, SHOWN};
+
+    fn entry(score: i64, seconds: u64) -> HighScore {
+        HighScore {
+            score,
+            duration: Duration::from_secs(seconds),
+            dictionary: "test".to_string(),
+            mode: "normal".to_string(),
+            hints: 0,
+        }
+    }
+
+    #[test]
+    fn test_placing() {
+        let mut scores: HighScores = HighScores::default();
+        assert_eq!(scores.insert(entry(50, 60)), 0);
+        assert_eq!(scores.insert(entry(80, 60)), 0);
+        // Ties go to the quicker game, then to whoever got there first.
+        assert_eq!(scores.insert(entry(50, 30)), 1);
+        assert_eq!(scores.insert(entry(50, 60)), 3);
+        assert_eq!(scores.insert(entry(-5, 10)), 4);
+
+        for _ in 0..SHOWN {
+            scores.insert(entry(100, 1));
+        }
+        assert_eq!(scores.top().len(), SHOWN);
+        assert!(scores.top().iter().all(|x: &HighScore| x.score == 100));
+    }
+}
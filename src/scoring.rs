@@ -0,0 +1,186 @@
+//! How many points each letter is worth.
+//!
+//! The usual values are Scrabble's. A table read from a file goes over the top of
+//! them, so a house rule only has to list the letters it changes, and a table for
+//! another alphabet just adds its own letters. Either a CSV of `letter,points`
+//! rows or a TOML table works:
+//!
+//! ```toml
+//! q = 12
+//! "ñ" = 8
+//! ```
+
+use std::{collections::HashMap, fs, path::Path};
+
+use csv::{ReaderBuilder, StringRecord};
+
+use crate::Error;
+
+/// How many points each letter is worth, unless a table says otherwise.
+const LETTER_SCORES: [(char, i64); 26] = [
+    ('a', 1),
+    ('b', 3),
+    ('c', 3),
+    ('d', 2),
+    ('e', 1),
+    ('f', 4),
+    ('g', 2),
+    ('h', 4),
+    ('i', 1),
+    ('j', 8),
+    ('k', 5),
+    ('l', 1),
+    ('m', 3),
+    ('n', 1),
+    ('o', 1),
+    ('p', 3),
+    ('q', 10),
+    ('r', 1),
+    ('s', 1),
+    ('t', 1),
+    ('u', 1),
+    ('v', 4),
+    ('w', 4),
+    ('x', 8),
+    ('y', 4),
+    ('z', 10),
+];
+
+/// How many times its usual score the hot letter is worth.
+const HOT_MULTIPLIER: i64 = 3;
+
+/// Every letter's score.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scoring {
+    letters: HashMap<char, i64>,
+    /// A letter worth triple for the whole game, if there is one.
+    hot: Option<char>,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            letters: HashMap::from(LETTER_SCORES),
+            hot: None,
+        }
+    }
+}
+
+impl Scoring {
+    /// Reads a table of scores from a `.csv` or `.toml` file, over the usual ones.
+    ///
+    /// # Errors
+    ///
+    /// `Error::ScoringFailed` if the file can't be read, or has a row that isn't a letter and its points.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let fail = |exception: String| Error::ScoringFailed(exception);
+        let table: HashMap<char, i64> = match path.extension().and_then(|x| x.to_str()) {
+            Some("csv") => ReaderBuilder::new()
+                .has_headers(false)
+                .from_path(path)
+                .map_err(|x| fail(x.to_string()))?
+                .into_records()
+                .map(|record: Result<StringRecord, csv::Error>| {
+                    let record: StringRecord = record.map_err(|x| fail(x.to_string()))?;
+                    parse_row(&record).ok_or_else(|| {
+                        fail(format!(
+                            "\"{}\" isn't a letter and its points.",
+                            record.as_slice()
+                        ))
+                    })
+                })
+                .collect::<Result<_, Error>>()?,
+            Some("toml") => {
+                let text: String = fs::read_to_string(path).map_err(|x| fail(x.to_string()))?;
+                toml::from_str(&text).map_err(|x| fail(x.to_string()))?
+            }
+            _ => {
+                return Err(fail(format!(
+                    "{} isn't a .csv or .toml file.",
+                    path.display()
+                )));
+            }
+        };
+
+        let mut scoring: Self = Self::default();
+        scoring.extend(&table);
+        Ok(scoring)
+    }
+
+    /// Changes the scores of some letters.
+    pub fn extend(&mut self, changes: &HashMap<char, i64>) {
+        self.letters.extend(changes);
+    }
+
+    /// A letter's score, if it has one.
+    #[must_use]
+    pub fn get(&self, letter: char) -> Option<i64> {
+        self.letters.get(&letter).copied()
+    }
+
+    /// A tile's score. Anything without one, like a played blank, is worth nothing.
+    #[must_use]
+    pub fn points(&self, tile: char) -> i64 {
+        let points: i64 = self.get(tile).unwrap_or_default();
+        if self.hot == Some(tile) {
+            points * HOT_MULTIPLIER
+        } else {
+            points
+        }
+    }
+
+    #[must_use]
+    pub const fn hot(&self) -> Option<char> {
+        self.hot
+    }
+
+    /// Makes a letter worth triple, or none if it's `None`.
+    pub const fn set_hot(&mut self, letter: Option<char>) {
+        self.hot = letter;
+    }
+}
+
+/// Reads a `letter,points` row.
+fn parse_row(record: &StringRecord) -> Option<(char, i64)> {
+    let mut letters = record.get(0)?.trim().chars();
+    let letter: char = letters.next().filter(|_| letters.next().is_none())?;
+    Some((letter, record.get(1)?.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::Scoring;
+
+    #[test]
+    fn test_load_tables() {
+        let directory: PathBuf = env::temp_dir();
+        let csv: PathBuf = directory.join(format!("guavagrams-scores-{}.csv", std::process::id()));
+        let toml: PathBuf = csv.with_extension("toml");
+        fs::write(&csv, "q,12\nñ, 8\n").unwrap();
+        fs::write(&toml, "q = 12\n\"ñ\" = 8\n").unwrap();
+
+        for path in [&csv, &toml] {
+            let scoring: Scoring = Scoring::load(path).unwrap();
+            fs::remove_file(path).unwrap();
+            assert_eq!(scoring.points('q'), 12);
+            assert_eq!(scoring.points('ñ'), 8);
+            // Everything else keeps its usual score, and played blanks get nothing.
+            assert_eq!(scoring.points('z'), 10);
+            assert_eq!(scoring.points('Z'), 0);
+        }
+
+        // The hot letter is worth triple, but not as a played blank.
+        let mut scoring: Scoring = Scoring::default();
+        scoring.set_hot(Some('k'));
+        assert_eq!(scoring.points('k'), 15);
+        assert_eq!(scoring.points('K'), 0);
+        assert_eq!(scoring.get('k'), Some(5));
+
+        fs::write(&csv, "q,lots\n").unwrap();
+        assert!(Scoring::load(&csv).is_err());
+        fs::remove_file(&csv).unwrap();
+        assert!(Scoring::load(&csv.with_extension("json")).is_err());
+    }
+}
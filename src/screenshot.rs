@@ -0,0 +1,258 @@
+//! Saves a frame exactly as it was drawn, colors and all.
+//!
+//! Screenshots are written twice: as ANSI art (`.ans`) for terminals, and as
+//! HTML for everywhere else.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier, Style},
+};
+
+use crate::Error;
+
+/// Where screenshots are saved, relative to the working directory.
+const SCREENSHOT_DIRECTORY: &str = "screenshots";
+
+/// The first 16 colors, as xterm draws them.
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Where a color is in the 256-color palette, if it's a named or indexed one.
+const fn palette_index(color: Color) -> Option<u8> {
+    Some(match color {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(index) => index,
+        Color::Reset | Color::Rgb(..) => return None,
+    })
+}
+
+/// Works out the red, green and blue of a color, or `None` for the terminal's default.
+fn to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    if let Color::Rgb(r, g, b) = color {
+        return Some((r, g, b));
+    }
+    let index: u8 = palette_index(color)?;
+    Some(match index {
+        0..16 => BASIC_COLORS[usize::from(index)],
+        // A 6x6x6 color cube.
+        16..232 => {
+            let level = |x: u8| if x == 0 { 0 } else { 55 + x * 40 };
+            let cube: u8 = index - 16;
+            (level(cube / 36), level(cube / 6 % 6), level(cube % 6))
+        }
+        // Then a ramp of greys.
+        232..=u8::MAX => {
+            let grey: u8 = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    })
+}
+
+/// The SGR parameters that switch a terminal to a style, starting from a reset.
+fn sgr(style: Style) -> String {
+    let mut codes: Vec<String> = vec!["0".to_string()];
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    for (color, layer) in [(style.fg, 3), (style.bg, 4)] {
+        match color.unwrap_or(Color::Reset) {
+            Color::Reset => (),
+            Color::Rgb(r, g, b) => codes.push(format!("{layer}8;2;{r};{g};{b}")),
+            color => {
+                if let Some(index) = palette_index(color) {
+                    codes.push(format!("{layer}8;5;{index}"));
+                }
+            }
+        }
+    }
+    codes.join(";")
+}
+
+/// Splits each row of a buffer into runs of text that share a style.
+fn runs(buffer: &Buffer) -> Vec<Vec<(Style, String)>> {
+    let width: usize = usize::from(buffer.area.width.max(1));
+    buffer
+        .content
+        .chunks(width)
+        .map(|row: &[Cell]| {
+            let mut runs: Vec<(Style, String)> = Vec::new();
+            for cell in row.iter().filter(|cell: &&Cell| !cell.skip) {
+                let style: Style = cell.style();
+                match runs.last_mut() {
+                    Some((last, text)) if *last == style => text.push_str(cell.symbol()),
+                    _ => runs.push((style, cell.symbol().to_string())),
+                }
+            }
+            runs
+        })
+        .collect()
+}
+
+/// Draws each row of a buffer as a line of ANSI art, without line endings.
+pub fn ansi_lines(buffer: &Buffer) -> Vec<String> {
+    runs(buffer)
+        .into_iter()
+        .map(|row: Vec<(Style, String)>| {
+            let mut line: String = String::new();
+            for (style, text) in row {
+                write!(&mut line, "\x1b[{}m{text}", sgr(style)).unwrap();
+            }
+            line.push_str("\x1b[0m");
+            line
+        })
+        .collect()
+}
+
+/// Draws a buffer as ANSI art.
+pub fn to_ansi(buffer: &Buffer) -> String {
+    ansi_lines(buffer)
+        .into_iter()
+        .map(|line: String| line + "\n")
+        .collect()
+}
+
+/// Draws a buffer as a standalone HTML page.
+pub fn to_html(buffer: &Buffer) -> String {
+    let mut output: String = String::from(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Guavagrams</title></head>\n\
+         <body style=\"background: #000\">\n<pre style=\"color: #e5e5e5; background: #000; \
+         font-family: monospace; line-height: 1.2\">",
+    );
+    for row in runs(buffer) {
+        for (style, text) in row {
+            let (mut fg, mut bg) = (style.fg.and_then(to_rgb), style.bg.and_then(to_rgb));
+            if style.add_modifier.contains(Modifier::REVERSED) {
+                (fg, bg) = (
+                    Some(bg.unwrap_or((0, 0, 0))),
+                    Some(fg.unwrap_or((229, 229, 229))),
+                );
+            }
+
+            let mut css: String = String::new();
+            if let Some((r, g, b)) = fg {
+                write!(&mut css, "color: #{r:02x}{g:02x}{b:02x}; ").unwrap();
+            }
+            if let Some((r, g, b)) = bg {
+                write!(&mut css, "background: #{r:02x}{g:02x}{b:02x}; ").unwrap();
+            }
+            for (modifier, rule) in [
+                (Modifier::BOLD, "font-weight: bold; "),
+                (Modifier::DIM, "opacity: 0.6; "),
+                (Modifier::ITALIC, "font-style: italic; "),
+                (Modifier::UNDERLINED, "text-decoration: underline; "),
+                (Modifier::CROSSED_OUT, "text-decoration: line-through; "),
+            ] {
+                if style.add_modifier.contains(modifier) {
+                    css.push_str(rule);
+                }
+            }
+
+            let text: String = text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+            if css.is_empty() {
+                output.push_str(&text);
+            } else {
+                write!(
+                    &mut output,
+                    "<span style=\"{}\">{text}</span>",
+                    css.trim_end()
+                )
+                .unwrap();
+            }
+        }
+        output.push('\n');
+    }
+    output.push_str("</pre>\n</body>\n</html>\n");
+    output
+}
+
+/// Saves a buffer as both ANSI art and HTML, returning the path without an extension.
+pub fn save(buffer: &Buffer) -> Result<PathBuf, Error> {
+    let timestamp: u128 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |x| x.as_millis());
+    let path: PathBuf = PathBuf::from(SCREENSHOT_DIRECTORY).join(timestamp.to_string());
+
+    fs::create_dir_all(SCREENSHOT_DIRECTORY)
+        .and_then(|()| fs::write(path.with_extension("ans"), to_ansi(buffer)))
+        .and_then(|()| fs::write(path.with_extension("html"), to_html(buffer)))
+        .map_err(|exception| Error::ScreenshotFailed(exception.to_string()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::{Color, Style, Stylize as _},
+    };
+
+    use super::{to_ansi, to_html};
+
+    #[test]
+    fn test_colors_survive() {
+        let mut buffer: Buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(0, 0, "ab", Style::new().fg(Color::Red).bold());
+        buffer.set_string(0, 1, "<c>", Style::new().bg(Color::Rgb(1, 2, 3)));
+
+        assert_eq!(
+            to_ansi(&buffer),
+            "\x1b[0;1;38;5;1mab\x1b[0m  \x1b[0m\n\x1b[0;48;2;1;2;3m<c>\x1b[0m \x1b[0m\n"
+        );
+
+        let html: String = to_html(&buffer);
+        assert!(html.contains("<span style=\"color: #cd0000; font-weight: bold;\">ab</span>  \n"));
+        assert!(html.contains("<span style=\"background: #010203;\">&lt;c&gt;</span> \n"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Finding a word to play, for hints.
+//!
+//! This isn't a full search. A suggested word either goes down on an empty board, or
+//! crosses exactly one tile already there without touching any others, so it can't
+//! make a bad word by accident.
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use crate::{
+    dictionary::{BLANK, Dictionary},
+    grid::{Coordinate, Grid, Orientation, Word},
+    rules::Rules,
+    scoring::Scoring,
+};
+
+/// A word that can be played, and where it goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub word: Word,
+    /// The tiles that come out of the hand, and where they go.
+    pub placements: Vec<(Coordinate, char)>,
+}
+
+/// Finds the best word to play from a hand: the one that uses the most tiles, then
+/// scores the most.
+///
+/// On an empty board, the word starts at `origin`. Blanks are kept back.
+pub fn suggest(
+    hand: &[char],
+    grid: &Grid<Option<char>>,
+    dictionary: &Dictionary,
+    scoring: &Scoring,
+    rules: &Rules,
+    origin: Coordinate,
+) -> Option<Hint> {
+    let mut in_hand: HashMap<char, usize> = HashMap::new();
+    for tile in hand.iter().filter(|x: &&char| **x != BLANK) {
+        *in_hand.entry(*tile).or_default() += 1;
+    }
+    // Played blanks are kept in uppercase, but read as the letter they stand for.
+    let mut anchors: HashMap<char, Vec<Coordinate>> = HashMap::new();
+    for (coordinate, tile) in grid.occupied_cells() {
+        if let Some(letter) = tile.and_then(|x: char| x.to_lowercase().next()) {
+            anchors.entry(letter).or_default().push(coordinate);
+        }
+    }
+
+    let mut candidates: Vec<Hint> = Vec::new();
+    for text in dictionary.words() {
+        let letters: Vec<char> = text.chars().collect();
+        let Some(spelling) = shortfall(&letters, &in_hand) else {
+            continue;
+        };
+        if letters.len() < 2 {
+            continue;
+        }
+
+        if anchors.is_empty() {
+            if spelling == Spelling::Whole {
+                candidates.extend(fit(grid, &letters, origin, Orientation::Horizontal, None));
+            }
+            continue;
+        }
+        // The letter crossed on the board has to be the one the hand is short of, if it's short.
+        for (index, letter) in letters.iter().enumerate() {
+            if matches!(spelling, Spelling::ShortOf(x) if x != *letter) {
+                continue;
+            }
+            for anchor in anchors.get(letter).into_iter().flatten() {
+                for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+                    let Some(start) =
+                        (0..index).try_fold(*anchor, |x: Coordinate, _| orientation.step(x, false))
+                    else {
+                        continue;
+                    };
+                    candidates.extend(fit(grid, &letters, start, orientation, Some(index)));
+                }
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|hint: &Hint| {
+            rules
+                .validate_words(std::slice::from_ref(&hint.word))
+                .is_ok()
+        })
+        // Ties are broken by the word and where it goes, so the same board always gets the same hint.
+        .max_by_key(|hint: &Hint| {
+            (
+                hint.placements.len(),
+                Grid::score_grid(std::slice::from_ref(&hint.word), scoring, rules),
+                Reverse((
+                    hint.word.text.clone(),
+                    hint.word.start.0,
+                    hint.word.start.1,
+                    hint.word.orientation == Orientation::Vertical,
+                )),
+            )
+        })
+}
+
+/// How much of a word a hand can spell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spelling {
+    /// The hand has every letter.
+    Whole,
+    /// The hand is one of this letter short, which could come from the board.
+    ShortOf(char),
+}
+
+/// Whether a hand can spell a word with at most one letter to spare from the board.
+fn shortfall(letters: &[char], in_hand: &HashMap<char, usize>) -> Option<Spelling> {
+    let mut needed: HashMap<char, usize> = HashMap::new();
+    for letter in letters {
+        *needed.entry(*letter).or_default() += 1;
+    }
+    let mut spelling: Spelling = Spelling::Whole;
+    for (letter, count) in needed {
+        match count.saturating_sub(in_hand.get(&letter).copied().unwrap_or_default()) {
+            0 => (),
+            1 if spelling == Spelling::Whole => spelling = Spelling::ShortOf(letter),
+            _ => return None,
+        }
+    }
+    Some(spelling)
+}
+
+/// Lays a word out from `start`, if it fits.
+///
+/// Only the cell at `crossing` can already have a tile on it. Every other cell has to
+/// be empty, with nothing beside it, and nothing can be right before or after the word.
+fn fit(
+    grid: &Grid<Option<char>>,
+    letters: &[char],
+    start: Coordinate,
+    orientation: Orientation,
+    crossing: Option<usize>,
+) -> Option<Hint> {
+    let across: Orientation = match orientation {
+        Orientation::Horizontal => Orientation::Vertical,
+        Orientation::Vertical => Orientation::Horizontal,
+    };
+    let empty = |x: Option<Coordinate>| x.is_none_or(|x: Coordinate| grid[x].is_none());
+    if !empty(orientation.step(start, false)) {
+        return None;
+    }
+
+    let mut placements: Vec<(Coordinate, char)> = Vec::new();
+    let mut coordinate: Coordinate = start;
+    for (index, letter) in letters.iter().enumerate() {
+        if index > 0 {
+            coordinate = orientation.step(coordinate, true)?;
+        }
+        if Some(index) != crossing {
+            if !empty(Some(coordinate))
+                || !empty(across.step(coordinate, true))
+                || !empty(across.step(coordinate, false))
+            {
+                return None;
+            }
+            placements.push((coordinate, *letter));
+        }
+    }
+    if !empty(orientation.step(coordinate, true)) {
+        return None;
+    }
+
+    Some(Hint {
+        word: Word {
+            text: letters.iter().collect(),
+            start,
+            orientation,
+        },
+        placements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hint, suggest};
+    use crate::{
+        dictionary::{Dictionary, Normalization},
+        grid::{Coordinate, Grid, Orientation},
+        rules::{Mutator, Rules},
+        scoring::Scoring,
+    };
+
+    #[test]
+    fn test_suggest() {
+        let dictionary: Dictionary = Dictionary::new(
+            ["at", "cat", "tax", "taxi"].map(String::from),
+            Normalization::default(),
+        );
+        let hand = |letters: &str| -> Vec<char> { letters.chars().collect() };
+        let hint = |hand: &[char], grid: &Grid<Option<char>>, rules: &Rules| -> Option<Hint> {
+            suggest(
+                hand,
+                grid,
+                &dictionary,
+                &Scoring::default(),
+                rules,
+                Coordinate(5, 5),
+            )
+        };
+        let mut grid: Grid<Option<char>> = Grid::default();
+
+        // On an empty board, the longest word goes down at the origin, and "tax" beats
+        // "cat" on points.
+        let first: Hint = hint(&hand("catx"), &grid, &Rules::default()).unwrap();
+        assert_eq!(first.word.text, "tax");
+        assert_eq!(first.placements[0], (Coordinate(5, 5), 't'));
+        // Words shorter than a mutator allows aren't suggested.
+        let long: Rules = Rules {
+            mutators: vec![Mutator::MinimumLength(4)],
+        };
+        assert_eq!(hint(&hand("cat"), &grid, &long), None);
+
+        // With "cat" across the board, "taxi" can come down through its 't'.
+        for (x, letter) in (0..).zip("cat".chars()) {
+            grid[Coordinate(x, 0)] = Some(letter);
+        }
+        let cross: Hint = hint(&hand("axi"), &grid, &Rules::default()).unwrap();
+        assert_eq!(cross.word.text, "taxi");
+        assert_eq!(cross.word.start, Coordinate(2, 0));
+        assert_eq!(cross.word.orientation, Orientation::Vertical);
+        assert_eq!(
+            cross.placements,
+            [
+                (Coordinate(2, -1), 'a'),
+                (Coordinate(2, -2), 'x'),
+                (Coordinate(2, -3), 'i')
+            ]
+        );
+
+        // Nothing fits without a letter from the board to go through.
+        assert_eq!(hint(&hand("q"), &grid, &Rules::default()), None);
+    }
+}
@@ -0,0 +1,172 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// The face-down tiles nobody has drawn yet.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pile(Vec<char>);
+
+/// The tiles a player is holding but hasn't put down.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Hand {
+    tiles: Vec<char>,
+    /// The tiles drawn since the last peel that are still in the hand.
+    new_tiles: Vec<char>,
+}
+
+impl Pile {
+    pub const fn new(tiles: Vec<char>) -> Self {
+        Self(tiles)
+    }
+
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn tiles(&self) -> &[char] {
+        &self.0
+    }
+
+    /// Takes random tiles out of the pile.
+    pub fn pull(&mut self, amount: usize, rng: &mut impl Rng) -> Result<Vec<char>, Error> {
+        if self.0.len() < amount {
+            return Err(Error::NoMoreTiles);
+        }
+        Ok((0..amount)
+            .map(|_| self.0.swap_remove(rng.random_range(..self.0.len())))
+            .collect())
+    }
+
+    /// Moves random tiles from the pile into a hand.
+    pub fn deal(
+        &mut self,
+        hand: &mut Hand,
+        amount: usize,
+        rng: &mut impl Rng,
+    ) -> Result<(), Error> {
+        let mut tiles: Vec<char> = self.pull(amount, rng)?;
+        hand.new_tiles.extend_from_slice(&tiles);
+        hand.tiles.append(&mut tiles);
+        Ok(())
+    }
+
+    /// Trades a tile from a hand for three from the pile.
+    ///
+    /// Nothing changes unless the trade can go through in full.
+    pub fn dump(&mut self, hand: &mut Hand, letter: char, rng: &mut impl Rng) -> Result<(), Error> {
+        if !hand.contains(letter) {
+            return Err(Error::NotInHand(letter));
+        }
+        if self.len() < 3 {
+            return Err(Error::PileTooSmall(self.len()));
+        }
+        self.deal(hand, 3, rng)?;
+        // The drawn tiles went on the end, so the first match is still the one being dumped.
+        if let Some(tile) = hand.take(letter) {
+            self.0.push(tile);
+        }
+        Ok(())
+    }
+}
+
+impl Hand {
+    pub const fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    pub fn tiles(&self) -> &[char] {
+        &self.tiles
+    }
+
+    /// The tiles drawn since the last peel that haven't been put down yet.
+    pub fn new_tiles(&self) -> &[char] {
+        &self.new_tiles
+    }
+
+    pub fn contains(&self, letter: char) -> bool {
+        self.tiles.contains(&letter)
+    }
+
+    /// Puts a newly drawn tile into the hand.
+    pub fn draw(&mut self, tile: char) {
+        self.new_tiles.push(tile);
+        self.tiles.push(tile);
+    }
+
+    /// Puts a tile back into the hand, such as one picked up off the board.
+    pub fn add(&mut self, tile: char) {
+        self.tiles.push(tile);
+    }
+
+    /// Removes one of a letter from the hand, if there's one to remove.
+    pub fn take(&mut self, letter: char) -> Option<char> {
+        let position: usize = self.tiles.iter().position(|x: &char| *x == letter)?;
+        let tile: char = self.tiles.remove(position);
+
+        // Only forget a new tile once there are fewer of the letter than there were new ones.
+        let remaining: usize = self.tiles.iter().filter(|x: &&char| **x == letter).count();
+        let new: usize = self
+            .new_tiles
+            .iter()
+            .filter(|x: &&char| **x == letter)
+            .count();
+        if new > remaining
+            && let Some(position) = self.new_tiles.iter().position(|x: &char| *x == letter)
+        {
+            self.new_tiles.remove(position);
+        }
+        Some(tile)
+    }
+
+    /// Stops treating any tiles as newly drawn.
+    pub fn forget_new_tiles(&mut self) {
+        self.new_tiles.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{Hand, Pile};
+
+    #[test]
+    fn test_tiles_are_conserved() {
+        let mut rng: StdRng = StdRng::seed_from_u64(0);
+        let mut pile: Pile = Pile::new("abcdefghij".chars().collect());
+        let mut hand: Hand = Hand::default();
+
+        pile.deal(&mut hand, 4, &mut rng).unwrap();
+        assert_eq!((pile.len(), hand.len()), (6, 4));
+
+        hand.forget_new_tiles();
+        let letter: char = hand.tiles()[0];
+        pile.dump(&mut hand, letter, &mut rng).unwrap();
+        assert_eq!(hand.new_tiles().len(), 3);
+        assert_eq!((pile.len(), hand.len()), (4, 6));
+
+        // Every tile ends up in exactly one place.
+        let mut tiles: Vec<char> = [pile.tiles(), hand.tiles()].concat();
+        tiles.sort_unstable();
+        assert_eq!(tiles.into_iter().collect::<String>(), "abcdefghij");
+
+        // A failed dump doesn't move anything.
+        assert!(pile.deal(&mut hand, 5, &mut rng).is_err());
+        assert!(pile.dump(&mut hand, '?', &mut rng).is_err());
+        assert_eq!((pile.len(), hand.len()), (4, 6));
+
+        // Trading in needs three tiles left to draw.
+        pile.deal(&mut hand, 2, &mut rng).unwrap();
+        let letter: char = hand.tiles()[0];
+        assert!(matches!(
+            pile.dump(&mut hand, letter, &mut rng),
+            Err(crate::Error::PileTooSmall(2))
+        ));
+        assert_eq!((pile.len(), hand.len()), (2, 8));
+    }
+}
@@ -1,24 +1,139 @@
-use std::{rc::Rc, time::Instant};
+use std::{
+    borrow::Cow,
+    io::stdout,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::{
+    clipboard::CopyToClipboard,
+    event::{
+        self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
+    execute,
+};
 use itertools::Itertools;
-use rand::{rngs::ThreadRng, seq::SliceRandom};
+use rand::seq::SliceRandom;
 use ratatui::{
-    Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style, Styled, Stylize as _},
-    text::{Line, Span, Text},
-    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Position, Rect},
+    style::{Color, Modifier, Style, Styled, Stylize as _},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
+use serde::Deserialize;
+use unicode_width::UnicodeWidthStr;
+
 use crate::{
-    Error, EventResponse, GameState,
-    dictionary::Distribution,
-    grid::{Coordinate, Grid},
-    util::{format_duration, format_tile_list},
+    Error, EventResponse, GamePhase, GameState, Metrics, PauseChoice,
+    action::{self, Action},
+    analysis::{DifficultyRating, Sprawl, Stuck},
+    camera::{self, Selection},
+    dictionary::{self, BLANK, HOUSE_PATH, count_words},
+    grid::{Coordinate, Word},
+    history,
+    keymap::DoubleTap,
+    legality, notation,
+    practice::Attempts,
+    profile::RenderCosts,
+    replay,
+    rules::MUTATORS,
+    save::{self, SAVE_DIRECTORY},
+    scores::{SHOWN, Standing},
+    util::{
+        TimerFormat, count_tiles, count_tiles_in, draw_probability, format_duration, format_timer,
+        is_vowel,
+    },
 };
 
+/// The controls listed on screen, as (key, description).
+const KEYS: [(&str, &str); 24] = [
+    ("↑/↓/←/→", "Move"),
+    ("Any Letter", "Place"),
+    ("Backspace", "Pick Up"),
+    ("Shift + ↑/↓/←/→", "Pick Up Run"),
+    ("Shift + M", "Select/Lift/Drop Tiles"),
+    ("Ctrl + Any Letter", "Trade In"),
+    ("Alt + Any Letter", "Watch Letter"),
+    ("?", "Hint (Costs Points)"),
+    ("Shift + G", "Peel/Guavagrams!"),
+    ("Shift + C", "Copy Seed/Result"),
+    ("Shift + E", "Export Game"),
+    ("Shift + S", "Save Game"),
+    ("Shift + H", "Add To House Dictionary"),
+    ("Shift + P", "Pause"),
+    // Not u and Ctrl + R: plain letters place tiles, and Ctrl + a letter trades it in.
+    ("Shift + U/R", "Undo/Redo"),
+    ("Shift + F", "Screenshot"),
+    ("Shift + N/A", "Step/Apply Import"),
+    ("Space, +/-", "Play Import, Speed"),
+    ("Shift + Z/O", "Shuffle/Sort Hand"),
+    ("Shift + V", "Cursor Style"),
+    ("Shift + X", "Crosshair"),
+    ("Shift + D", "Debug Overlay"),
+    ("Shift + L", "Legality Report"),
+    ("Shift + Q/Esc", "Quit"),
+];
+
+/// How the clock in the game's title is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Timer {
+    /// How the time so far is written, in games without a time limit.
+    pub clock: TimerFormat,
+    /// How the time left is written, in timed games.
+    pub countdown: TimerFormat,
+    /// How many seconds are left when the countdown turns yellow.
+    pub warning_secs: u64,
+    /// How many seconds are left when it turns red.
+    pub danger_secs: u64,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self {
+            clock: TimerFormat::Compact,
+            countdown: TimerFormat::Tenths,
+            warning_secs: 60,
+            danger_secs: 10,
+        }
+    }
+}
+
+/// The game block's title: the time so far, or in a timed game, the time left,
+/// colored in as it runs out.
+fn timer_title(state: &GameState) -> Line<'static> {
+    let timer: Timer = state.timer;
+    let Some(limit) = state.time_limit else {
+        return Line::raw(format!(
+            " Game, ({}) ",
+            format_timer(state.elapsed(), timer.clock)
+        ));
+    };
+    let left: Duration = limit.saturating_sub(state.elapsed());
+    let style: Style = if left <= Duration::from_secs(timer.danger_secs) {
+        Style::new().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else if left <= Duration::from_secs(timer.warning_secs) {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new()
+    };
+    Line::from(vec![
+        Span::raw(" Game, ("),
+        Span::styled(
+            format!("{} left", format_timer(left, timer.countdown)),
+            style,
+        ),
+        Span::raw(") "),
+    ])
+}
+
 pub fn draw(frame: &mut Frame, state: &mut GameState) {
+    let mut costs: RenderCosts = RenderCosts::default();
+    let mut started: Instant = Instant::now();
     let layout: Rc<[Rect]> = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
@@ -28,15 +143,7 @@ pub fn draw(frame: &mut Frame, state: &mut GameState) {
     let block: Block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Double)
-        .title(format!(
-            " Game, ({}) ",
-            format_duration(
-                state
-                    .game_end
-                    .unwrap_or_else(Instant::now)
-                    .duration_since(state.game_start)
-            )
-        ))
+        .title(timer_title(state))
         .title_bottom(state.status.clone())
         .title_alignment(Alignment::Center);
     let block_layout: Rc<[Rect]> = Layout::default()
@@ -45,31 +152,44 @@ pub fn draw(frame: &mut Frame, state: &mut GameState) {
         .spacing(1)
         .split(block.inner(layout[0]));
 
-    let tiles_block: Block = Block::default()
-        .border_type(BorderType::Plain)
-        .borders(Borders::TOP)
-        .title("Your Tiles")
-        .title_alignment(Alignment::Center);
-
     frame.render_widget(&block, layout[0]);
 
-    let keys = [
-        ("↑/↓/←/→", "Move"),
-        ("Any Letter", "Place"),
-        ("Del", "Pick Up"),
-        ("Ctrl + Any Letter", "Trade In"),
-        ("Shift + G", "Peel/Guavagrams!"),
-        ("Shift + Q/Esc", "Quit"),
-    ];
+    let [pile_line, draw_line] = pile_lines(state);
     let mut lines = vec![
         Line::raw(format!("Coordinates: {}", state.camera.cursor)),
-        Line::raw(format!("Tiles left in pile: {}", state.tileset.0.len())),
-        Line::raw(format!("Score: {}", state.score)),
-        Line::default(),
+        pile_line,
+        draw_line,
+        score_line(state),
+        seed_line(state),
+        difficulty_line(state),
+        Line::raw(format!(
+            "Tiles placed: {}, Words: {}, Board: {}x{}",
+            state.camera.stats.tiles,
+            state.camera.stats.words.len(),
+            state.camera.stats.dimensions.0,
+            state.camera.stats.dimensions.1
+        )),
     ];
+    lines.extend(mode_lines(state));
+    if let Some(style) = state.style {
+        lines.push(Line::raw(format!(
+            "Style: {}/100 ({:.0}% compact, {:.0}% symmetrical, {:.0}% crossed)",
+            style.total(),
+            style.compactness * 100.0,
+            style.symmetry * 100.0,
+            style.intersections * 100.0
+        )));
+    }
+    if !state.playback.is_empty() {
+        lines.push(Line::raw(format!(
+            "Imported moves left: {}",
+            state.playback.len()
+        )));
+    }
+    lines.push(Line::default());
 
     lines.append(
-        &mut keys
+        &mut KEYS
             .iter()
             .map(|(key, desc)| {
                 let key: Span = Span::styled(format!(" {key} "), Style::new().cyan());
@@ -80,119 +200,1260 @@ pub fn draw(frame: &mut Frame, state: &mut GameState) {
     );
 
     frame.render_widget(Paragraph::new(lines), block_layout[0]);
-    frame.render_widget(&tiles_block, block_layout[1]);
+    costs.panel = lap(&mut started);
+    // Nothing to think over while the clock's stopped, so the board and hand are hidden.
+    if state.paused_since.is_some() {
+        frame.render_widget(Block::bordered(), layout[1]);
+    } else {
+        // Once the game's over, the hand makes way for the high scores.
+        match &state.standing {
+            Some(standing) if state.phase == GamePhase::Finished => {
+                draw_high_scores(frame, standing, block_layout[1]);
+            }
+            _ => draw_hand(frame, state, block_layout[1]),
+        }
+        costs.hand = lap(&mut started);
+        if state.phase == GamePhase::Setup {
+            draw_distribution_preview(frame, state, layout[1]);
+        } else {
+            frame.render_widget(&mut state.camera, layout[1]);
+        }
+        costs.board = lap(&mut started);
+    }
+
+    if state.debug_overlay {
+        draw_debug_overlay(frame, state.metrics, state.camera.stats.checksum);
+    }
+    if let Some(report) = &state.legality_report {
+        draw_legality_report(frame, report);
+    }
+    if state.paused_since.is_some() {
+        draw_pause_menu(frame, state.pause_choice);
+    }
+    costs.overlays = lap(&mut started);
+    state.metrics.render = costs;
+}
+
+/// How long it's been since `started`, starting the clock again for the next part.
+fn lap(started: &mut Instant) -> Duration {
+    let now: Instant = Instant::now();
+    let lap: Duration = now.duration_since(*started);
+    *started = now;
+    lap
+}
+
+/// Shows how the main loop is keeping up, in the top right corner.
+fn draw_debug_overlay(frame: &mut Frame, metrics: Metrics, checksum: u64) {
+    let lines: Vec<Line> = vec![
+        Line::raw(format!(
+            "Input queue: {} (peak {})",
+            metrics.input_depth, metrics.input_peak
+        )),
+        Line::raw(format!(
+            "Input time: {:.1}ms",
+            metrics.input_time.as_secs_f64() * 1000.0
+        )),
+        Line::raw(format!(
+            "Frame time: {:.1}ms",
+            metrics.frame_time.as_secs_f64() * 1000.0
+        )),
+        Line::raw(format!(
+            "Board time: {:.1}ms",
+            metrics.render.board.as_secs_f64() * 1000.0
+        )),
+        Line::raw(format!("Checksum: {checksum:016x}")),
+    ];
+    let [area] = Layout::vertical([Constraint::Length(7)]).areas(frame.area());
+    let [_, area] = Layout::horizontal([Constraint::Fill(1), Constraint::Length(30)]).areas(area);
+
+    frame.render_widget(Clear, area);
     frame.render_widget(
-        Paragraph::new(Text::from(format_tile_list(&state.tileset.1))).wrap(Wrap { trim: false }),
-        tiles_block.inner(block_layout[1]),
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Debug ")
+                .title_alignment(Alignment::Center),
+        ),
+        area,
     );
-    frame.render_widget(&mut state.camera, layout[1]);
 }
 
-/// The event logic.
+/// Shows the legality report over the board, taken when Shift + L was pressed.
+fn draw_legality_report(frame: &mut Frame, report: &str) {
+    let [area] = Layout::vertical([Constraint::Percentage(80)])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(report).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Legality Report ")
+                .title_bottom(" Shift + L to close ")
+                .title_alignment(Alignment::Center),
+        ),
+        area,
+    );
+}
+
+/// Shows how hard the starting hand looked, colored by its rating.
+fn difficulty_line(state: &GameState) -> Line<'static> {
+    let Some(difficulty) = state.difficulty else {
+        return Line::default();
+    };
+    let rating: DifficultyRating = difficulty.rating();
+    Line::from(vec![
+        Span::raw("Starting hand: "),
+        Span::styled(
+            format!(" {rating} "),
+            Style::new().fg(Color::Black).bg(match rating {
+                DifficultyRating::Easy => Color::Green,
+                DifficultyRating::Medium => Color::Yellow,
+                DifficultyRating::Hard => Color::Red,
+            }),
+        ),
+        Span::raw(format!(
+            " ({:.0}% vowels, {} rare, longest word {})",
+            difficulty.vowel_ratio * 100.0,
+            difficulty.rare_letters,
+            difficulty.longest_word
+        )),
+    ])
+}
+
+/// Shows the hand grouped by letter, a page at a time, with newly drawn tiles highlighted.
+fn draw_hand(frame: &mut Frame, state: &mut GameState, area: Rect) {
+    /// How many columns each letter takes up.
+    const GROUP_WIDTH: u16 = 10;
+
+    let mut groups: Vec<(char, usize)> = count_tiles_in(state.hand.tiles(), &state.hand_order);
+    if state.group_hand && state.hand_order.is_empty() {
+        groups.sort_by_key(|(tile, ..)| !is_vowel(*tile));
+    }
+    let new_groups: Vec<(char, usize)> = count_tiles(state.hand.new_tiles());
+
+    let mut block: Block = Block::default()
+        .border_type(BorderType::Plain)
+        .borders(Borders::TOP)
+        .title(format!("Your Tiles ({})", state.hand.len()))
+        .title_alignment(Alignment::Center);
+    let inner: Rect = block.inner(area);
+    let columns: usize = usize::from((inner.width / GROUP_WIDTH).max(1));
+    let rows: usize = usize::from(inner.height.max(1));
+    let per_page: usize = columns * rows;
+
+    let pages: usize = groups.len().div_ceil(per_page).max(1);
+    state.hand_page = state.hand_page.min(pages - 1);
+    if pages > 1 {
+        block = block.title_bottom(
+            Line::raw(format!(
+                " Page {}/{pages} (PgUp/PgDn) ",
+                state.hand_page + 1
+            ))
+            .right_aligned(),
+        );
+    }
+
+    let shown: Vec<&(char, usize)> = groups
+        .iter()
+        .skip(state.hand_page * per_page)
+        .take(per_page)
+        .collect_vec();
+    let across: u16 = (inner.width / GROUP_WIDTH).max(1);
+    state.hand_spots = (0_u16..)
+        .zip(&shown)
+        .map(|(index, (tile, ..))| {
+            let spot: Rect = Rect::new(
+                inner.x + index % across * GROUP_WIDTH,
+                inner.y + index / across,
+                GROUP_WIDTH,
+                1,
+            );
+            (spot.intersection(inner), *tile)
+        })
+        .collect();
+    let lines: Vec<Line> = shown
+        .chunks(columns)
+        .map(|row: &[&(char, usize)]| {
+            Line::from(
+                row.iter()
+                    .flat_map(|(tile, amount)| {
+                        let new: usize = new_groups
+                            .iter()
+                            .find(|(x, ..)| x == tile)
+                            .map_or(0, |(.., x)| *x);
+                        let style: Style = if state.camera.held == Some(*tile) {
+                            Style::new().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+                        } else if new > 0 {
+                            Style::new().fg(Color::Green).add_modifier(Modifier::BOLD)
+                        } else if state.scoring.hot() == Some(*tile) {
+                            Style::new().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+                        } else if state.dead_weight.contains(tile) {
+                            Style::new()
+                                .fg(Color::DarkGray)
+                                .add_modifier(Modifier::CROSSED_OUT)
+                        } else if state.group_hand && is_vowel(*tile) {
+                            Style::new().fg(Color::Cyan)
+                        } else {
+                            Style::default()
+                        };
+                        let label: String = if new > 0 {
+                            format!("{tile} ×{amount} +{new}")
+                        } else {
+                            format!("{tile} ×{amount}")
+                        };
+                        [
+                            Span::styled(label.clone(), style),
+                            Span::raw(
+                                " ".repeat(usize::from(GROUP_WIDTH).saturating_sub(label.width())),
+                            ),
+                        ]
+                    })
+                    .collect_vec(),
+            )
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Shows the best games so far, picking out this one if it made the list.
+fn draw_high_scores(frame: &mut Frame, standing: &Standing, area: Rect) {
+    let title: String = if standing.place < SHOWN {
+        format!("High Scores (you placed #{}!)", standing.place + 1)
+    } else {
+        format!("High Scores (you placed #{})", standing.place + 1)
+    };
+    let block: Block = Block::default()
+        .border_type(BorderType::Plain)
+        .borders(Borders::TOP)
+        .title(title)
+        .title_alignment(Alignment::Center);
+
+    let lines: Vec<Line> = standing
+        .top
+        .iter()
+        .enumerate()
+        .map(|(place, entry)| {
+            let style: Style = if place == standing.place {
+                Style::new().fg(Color::Green).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Line::styled(
+                format!(
+                    "#{:<3} {:>6} pts  {}  {} ({}{})",
+                    place + 1,
+                    entry.score,
+                    format_duration(entry.duration),
+                    entry.dictionary,
+                    entry.mode,
+                    match entry.hints {
+                        0 => String::new(),
+                        1 => ", 1 hint".to_string(),
+                        hints => format!(", {hints} hints"),
+                    }
+                ),
+                style,
+            )
+        })
+        .collect();
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Shows what the pile was made of before the game starts.
 #[allow(clippy::cast_precision_loss)]
-pub fn event_handler(state: &mut GameState) -> Result<EventResponse, Error> {
-    if let Event::Key(event) = event::read().expect("failed to read event")
-        && event.kind == KeyEventKind::Press
-    {
-        match event.code {
-            // Quit game
-            KeyCode::Esc | KeyCode::Char('Q') => return Ok(EventResponse::Quit),
-
-            // Movement controls
-            KeyCode::Right => state.camera += Coordinate(1, 0),
-            KeyCode::Left => state.camera += Coordinate(-1, 0),
-            KeyCode::Up => state.camera += Coordinate(0, 1),
-            KeyCode::Down => state.camera += Coordinate(0, -1),
-
-            // Letter controls
-            KeyCode::Char('G') => {
-                if !state.tileset.1.is_empty() {
-                    return Err(Error::HandHasTiles);
+fn draw_distribution_preview(frame: &mut Frame, state: &GameState, area: Rect) {
+    let counts: Vec<(char, usize)> = state
+        .distribution
+        .tile_counts(state.pile_size, &state.rules);
+    let total: usize = counts.iter().map(|(.., x)| x).sum();
+    let vowels: usize = counts
+        .iter()
+        .filter(|(tile, ..)| is_vowel(*tile))
+        .map(|(.., x)| x)
+        .sum();
+
+    let mut lines: Vec<Line> = vec![
+        Line::raw(format!(
+            "{} distribution, {} tiles asked for",
+            state.distribution.name(),
+            state.pile_size
+        )),
+        Line::raw(format!("Total tiles: {total}")),
+        Line::raw(format!(
+            "Vowels: {vowels}, consonants: {} ({:.1}% vowels)",
+            total - vowels,
+            vowels as f64 / total.max(1) as f64 * 100.0
+        )),
+    ];
+    if total != state.pile_size {
+        lines.push(Line::styled(
+            format!(
+                "The pile is {} tiles {} than asked for!",
+                total.abs_diff(state.pile_size),
+                if total < state.pile_size {
+                    "smaller"
+                } else {
+                    "bigger"
                 }
+            ),
+            Style::new().fg(Color::Yellow),
+        ));
+    }
+    lines.push(Line::default());
+    lines.extend(counts.chunks(6).map(|row: &[(char, usize)]| {
+        Line::raw(
+            row.iter()
+                .map(|(tile, amount)| format!("{tile}: {amount:<3}"))
+                .join("  "),
+        )
+    }));
+    lines.push(Line::default());
+    lines.push(Line::raw("Mutators:"));
+    for (number, mutator) in MUTATORS.iter().enumerate() {
+        let enabled: bool = state.rules.contains(*mutator);
+        lines.push(Line::styled(
+            format!(
+                " {} [{}] {mutator}",
+                number + 1,
+                if enabled { 'x' } else { ' ' }
+            ),
+            if enabled {
+                Style::new().fg(Color::Green)
+            } else {
+                Style::default()
+            },
+        ));
+    }
+    lines.push(Line::default());
+    lines.push(Line::styled(
+        if state.drill.is_some() {
+            "Play out every tile in the rack. Press any key to start."
+        } else if state.challenge.is_some() {
+            "This week's challenge sets the mutators. Press any key to start."
+        } else {
+            "Press a number to toggle a mutator, or any other key to start."
+        },
+        Style::new().add_modifier(Modifier::BOLD),
+    ));
+
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Distribution Preview ")
+                .title_alignment(Alignment::Center),
+        ),
+        area,
+    );
+}
+
+/// Shows the seed, or the challenge or drill being played and how the drill has gone.
+fn seed_line(state: &GameState) -> Line<'static> {
+    Line::raw(match (&state.challenge, &state.drill) {
+        (Some(challenge), _) => format!("Seed: {} (Weekly #{})", state.seed, challenge.week),
+        (None, Some(drill)) => match state.drill_attempts {
+            Attempts { tries: 0, .. } => format!("Drill: {} (first try)", drill.name),
+            attempts => format!(
+                "Drill: {} (cleared {} of {}, {}%)",
+                drill.name,
+                attempts.cleared,
+                attempts.tries,
+                attempts.rate()
+            ),
+        },
+        (None, None) => format!("Seed: {}", state.seed),
+    })
+}
+
+/// Shows the score, with the last change to it fading out beside it.
+fn score_line(state: &GameState) -> Line<'static> {
+    /// How long a change to the score floats beside it.
+    const DELTA_LIFETIME: Duration = Duration::from_secs(1);
+
+    let mut line: Line = Line::raw(format!("Score: {}", state.score));
+    if let Some((delta, changed)) = state.last_delta {
+        let color: Color = if delta < 0 { Color::Red } else { Color::Green };
+        let age: Duration = changed.elapsed();
+        if age < DELTA_LIFETIME {
+            let fade: Modifier = if state.reduced_motion {
+                Modifier::empty()
+            } else if age < DELTA_LIFETIME / 2 {
+                Modifier::BOLD
+            } else {
+                Modifier::DIM
+            };
+            line.push_span(Span::styled(
+                format!("  {delta:+}"),
+                Style::new().fg(color).add_modifier(fade),
+            ));
+        } else {
+            line.push_span(Span::styled(
+                format!(" (last change: {delta:+})"),
+                Style::new().fg(color),
+            ));
+        }
+    }
+    line
+}
+
+/// Lines that only some games have: the target, the hot letter, the bot and the scoring rate.
+fn mode_lines(state: &GameState) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(target) = state.target_score {
+        lines.push(target_line(state.score, target));
+    }
+    if let Some(letter) = state.scoring.hot() {
+        lines.push(Line::styled(
+            format!("Hot letter: {letter} (x3)"),
+            Style::new().fg(Color::Magenta),
+        ));
+    }
+    let hints: usize = state.hints_used();
+    if hints > 0 {
+        lines.push(Line::styled(
+            format!("Hints used: {hints} of {}", state.hints.per_game),
+            Style::new().fg(Color::Cyan),
+        ));
+    }
+    if let Some(bot) = &state.bot {
+        lines.push(Line::raw(format!(
+            "Bot: {} tiles in hand, {} on the board{}",
+            bot.hand.len(),
+            bot.grid.occupied_cells().count(),
+            if bot.stuck { " (stuck)" } else { "" }
+        )));
+        lines.push(Line::raw(match bot.last_peel {
+            Some(at) => format!(
+                "Bot's last peel: {} ({} so far)",
+                format_duration(at),
+                bot.peels
+            ),
+            None => "Bot's last peel: none yet".to_string(),
+        }));
+    }
+    if state.endless {
+        lines.push(Line::raw(format!(
+            "Score per minute: {:.1}",
+            score_per_minute(state.score, state.elapsed())
+        )));
+    }
+    // Playing in more than one language, the end of the game says how much of each was played.
+    if state.phase == GamePhase::Finished && state.dictionary.sources().len() > 1 {
+        let counts: Vec<(&str, usize)> = state.dictionary.count_by_source(
+            state
+                .camera
+                .stats
+                .words
+                .iter()
+                .map(|word: &Word| word.text.as_str()),
+        );
+        lines.push(Line::raw(format!(
+            "Words by dictionary: {}",
+            counts
+                .iter()
+                .map(|(name, count)| format!("{name} {count}"))
+                .join(", ")
+        )));
+    }
+    lines
+}
 
-                let words: Vec<String> = state.camera.grid.scan_for_words();
-                if let Err(exception) = state
-                    .camera
-                    .grid
-                    .validate_connectivity()
-                    .and_then(|()| Grid::validate_words(&words, &state.dictionary))
-                {
-                    state.score -= state.score / 20;
-                    return Err(exception);
+/// How close the score is to the target, as a bar.
+fn target_line(score: i64, target: i64) -> Line<'static> {
+    /// How many characters wide the bar is.
+    const WIDTH: i64 = 20;
+
+    let filled: usize =
+        usize::try_from(score.clamp(0, target) * WIDTH / target.max(1)).unwrap_or_default();
+    let empty: usize = usize::try_from(WIDTH).unwrap_or_default() - filled;
+    Line::from(vec![
+        Span::raw("Target: "),
+        Span::styled("█".repeat(filled), Style::new().fg(Color::Green)),
+        Span::styled("░".repeat(empty), Style::new().fg(Color::DarkGray)),
+        Span::raw(format!(" {score}/{target}")),
+    ])
+}
+
+/// How many tiles are left in the pile, and the odds of what comes next.
+fn pile_lines(state: &GameState) -> [Line<'static>; 2] {
+    // An endless pile never changes, so its odds are the distribution's own.
+    let draws: Cow<[char]> = if state.endless {
+        Cow::Owned(
+            state
+                .distribution
+                .tile_counts(state.pile_size, &state.rules)
+                .into_iter()
+                .flat_map(|(tile, count)| std::iter::repeat_n(tile, count))
+                .collect(),
+        )
+    } else {
+        Cow::Borrowed(state.pile.tiles())
+    };
+    [
+        Line::raw(if state.endless {
+            "Tiles left in pile: endless".to_string()
+        } else {
+            format!("Tiles left in pile: {}", state.pile.len())
+        }),
+        Line::raw(format!(
+            "Next draw: {:.1}% vowel{}",
+            draw_probability(&draws, is_vowel) * 100.0,
+            state
+                .watched_letter
+                .map(|letter: char| format!(
+                    ", {:.1}% '{letter}'",
+                    draw_probability(&draws, |tile: char| tile == letter) * 100.0
+                ))
+                .unwrap_or_default()
+        )),
+    ]
+}
+
+/// How fast points are coming in, over the whole game so far.
+#[allow(clippy::cast_precision_loss)]
+fn score_per_minute(score: i64, elapsed: Duration) -> f64 {
+    // Don't divide by nothing right as the game starts.
+    score as f64 / elapsed.as_secs_f64().max(1.0) * 60.0
+}
+
+/// Dims the screen and shows the pause menu over it.
+fn draw_pause_menu(frame: &mut Frame, choice: PauseChoice) {
+    const WIDTH: u16 = 32;
+
+    let area: Rect = frame.area();
+    frame
+        .buffer_mut()
+        .set_style(area, Style::new().add_modifier(Modifier::DIM));
+
+    let [popup] = Layout::vertical([Constraint::Length(6)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [popup] = Layout::horizontal([Constraint::Length(WIDTH)])
+        .flex(Flex::Center)
+        .areas(popup);
+
+    let option = |label: &'static str, option: PauseChoice| -> Line<'static> {
+        if option == choice {
+            Line::styled(
+                format!("> {label} <"),
+                Style::new().add_modifier(Modifier::REVERSED),
+            )
+        } else {
+            Line::raw(label)
+        }
+    };
+    let lines: Vec<Line> = vec![
+        option("Resume", PauseChoice::Resume),
+        option("Quit", PauseChoice::Quit),
+        Line::default(),
+        Line::styled(
+            "Or press any key to resume.",
+            Style::new().fg(Color::DarkGray),
+        ),
+    ];
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .title(" Paused ")
+                .title_alignment(Alignment::Center),
+        ),
+        popup,
+    );
+}
+
+/// Lets the player pick which dictionary to play with before the game starts.
+///
+/// Returns the index of the dictionary picked, or `None` if they quit instead.
+pub fn pick_dictionary(
+    terminal: &mut DefaultTerminal,
+    dictionaries: &[PathBuf],
+) -> std::io::Result<Option<Vec<usize>>> {
+    let counts: Vec<String> = dictionaries
+        .iter()
+        .map(|path: &PathBuf| {
+            count_words(path).map_or_else(
+                |_| "unreadable".to_string(),
+                |x: usize| format!("{x} words"),
+            )
+        })
+        .collect();
+    let mut list_state: ListState = ListState::default().with_selected(Some(0));
+    // Marked dictionaries are played with together.
+    let mut marked: Vec<bool> = vec![false; dictionaries.len()];
+
+    loop {
+        let items: Vec<ListItem> = dictionaries
+            .iter()
+            .zip(&counts)
+            .zip(&marked)
+            .map(|((path, count), marked)| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(if *marked { "[x] " } else { "[ ] " }),
+                    Span::raw(format!("{} ", path.display())),
+                    Span::styled(format!("({count})"), Style::new().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+        terminal.draw(|frame: &mut Frame| {
+            let [area] = Layout::vertical([Constraint::Length(
+                u16::try_from(items.len())
+                    .unwrap_or(u16::MAX)
+                    .saturating_add(4),
+            )])
+            .flex(Flex::Center)
+            .areas(frame.area());
+            let [area] = Layout::horizontal([Constraint::Percentage(50)])
+                .flex(Flex::Center)
+                .areas(area);
+            frame.render_stateful_widget(
+                List::new(items)
+                    .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+                    .highlight_symbol("> ")
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Double)
+                            .title(" Pick a Dictionary ")
+                            .title_bottom(" ↑/↓ Move, Space Mark, Enter Pick, Esc Quit ")
+                            .title_alignment(Alignment::Center),
+                    ),
+                area,
+                &mut list_state,
+            );
+        })?;
+
+        if let Event::Key(event) = event::read()?
+            && event.kind == KeyEventKind::Press
+        {
+            match event.code {
+                KeyCode::Up => list_state.select_previous(),
+                KeyCode::Down => list_state.select_next(),
+                KeyCode::Char(' ') => {
+                    if let Some(x) = list_state.selected() {
+                        let x: usize = x.min(dictionaries.len() - 1);
+                        marked[x] = !marked[x];
+                    }
                 }
+                // Nothing marked means just the highlighted one.
+                KeyCode::Enter if marked.contains(&true) => {
+                    return Ok(Some(
+                        (0..dictionaries.len())
+                            .filter(|x: &usize| marked[*x])
+                            .collect(),
+                    ));
+                }
+                KeyCode::Enter => {
+                    return Ok(list_state
+                        .selected()
+                        .map(|x: usize| vec![x.min(dictionaries.len() - 1)]));
+                }
+                KeyCode::Esc | KeyCode::Char('Q') => return Ok(None),
+                _ => (),
+            }
+        }
+    }
+}
 
-                state.score += Grid::score_grid(&words, &state.scoretable);
+/// The event logic.
+pub fn event_handler(state: &mut GameState, event: &Event) -> Result<EventResponse, Error> {
+    if let Event::Mouse(event) = *event {
+        return mouse_controls(state, event);
+    }
+    if let Event::Key(event) = *event
+        && event.kind == KeyEventKind::Press
+    {
+        state.last_input = Instant::now();
+        if state.paused_since.is_some() {
+            return Ok(pause_controls(state, event.code));
+        }
+        let mut keys: Vec<KeyEvent> = state.keymap.translate(event, Instant::now());
+        let Some(event) = keys.pop() else {
+            if state.keymap.latched().is_empty() {
+                return Ok(EventResponse::Pass);
+            }
+            return Ok(EventResponse::ChangeStatus(
+                format!("Holding {}, press a key.", state.keymap.latched())
+                    .set_style(Style::new().fg(Color::Cyan)),
+            ));
+        };
+        // A key held back for a double tap goes first.
+        for key in keys {
+            let response: Result<EventResponse, Error> = key_controls(state, key);
+            if state.respond(response).is_break() {
+                return Ok(EventResponse::Quit);
+            }
+        }
+        return key_controls(state, event);
+    }
+
+    Ok(EventResponse::Pass)
+}
+
+/// What a key press does, once the keymap has let it through.
+pub fn key_controls(state: &mut GameState, event: KeyEvent) -> Result<EventResponse, Error> {
+    // Any key during an auto-peel's countdown calls it off, and does nothing else.
+    if state.pending_peel.take().is_some() {
+        return Ok(EventResponse::ChangeStatus(
+            "Called off the peel. Press Shift + G when you're ready."
+                .set_style(Style::new().fg(Color::Cyan)),
+        ));
+    }
+
+    match event.code {
+        // Esc lets go of a selection before it quits.
+        KeyCode::Esc if state.camera.selection.take().is_some() => {
+            return Ok(EventResponse::Pass);
+        }
+
+        // Quit game
+        KeyCode::Esc | KeyCode::Char('Q') => return Ok(EventResponse::Quit),
+
+        // Nothing else happens until the game starts.
+        KeyCode::Char(number)
+            if state.phase == GamePhase::Setup
+                && state.challenge.is_none()
+                && state.drill.is_none()
+                && number.is_ascii_digit() =>
+        {
+            if let Some(mutator) = number
+                .to_digit(10)
+                .and_then(|x: u32| MUTATORS.get((x as usize).checked_sub(1)?))
+            {
+                state.rules.toggle(*mutator);
+                state.deal();
+            }
+        }
+        _ if state.phase == GamePhase::Setup => state.start(),
+
+        // Shift + an arrow picks up tiles that way instead of moving.
+        KeyCode::Right | KeyCode::Left | KeyCode::Up | KeyCode::Down
+            if event.modifiers.contains(KeyModifiers::SHIFT)
+                && state.phase == GamePhase::Playing =>
+        {
+            return playing_controls(state, event);
+        }
+
+        // Movement controls
+        KeyCode::Right => state.camera += Coordinate(1, 0),
+        KeyCode::Left => state.camera += Coordinate(-1, 0),
+        KeyCode::Up => state.camera += Coordinate(0, 1),
+        KeyCode::Down => state.camera += Coordinate(0, -1),
+
+        KeyCode::Char('C') => return copy(state),
+        KeyCode::Char('E') => return export(state),
+
+        // The frame is saved after it's next drawn, since only the main loop has it.
+        KeyCode::Char('F') => state.screenshot_requested = true,
+
+        // Display controls
+        KeyCode::PageUp => state.hand_page = state.hand_page.saturating_sub(1),
+        KeyCode::PageDown => state.hand_page += 1,
+        KeyCode::Char('Z') => return Ok(shuffle_hand(state)),
+        KeyCode::Char('O') => return Ok(sort_hand(state)),
+        KeyCode::Char('V') => state.camera.theme.cursor = state.camera.theme.cursor.next(),
+        KeyCode::Char('X') => state.camera.crosshair = !state.camera.crosshair,
+        KeyCode::Char('D') => state.debug_overlay = !state.debug_overlay,
+        KeyCode::Char('L') => {
+            state.legality_report = match state.legality_report {
+                Some(_) => None,
+                None => Some(legality::report(state)),
+            };
+        }
+
+        _ => match state.phase {
+            GamePhase::Setup | GamePhase::Finished => (),
+            GamePhase::Playing => return playing_controls(state, event),
+            GamePhase::Reviewing => return reviewing_controls(state, event.code),
+        },
+    }
+
+    Ok(EventResponse::Pass)
+}
 
-                if state.tileset.0.is_empty() {
-                    state.game_end = Some(Instant::now());
+/// Copies the seed to the clipboard, or once a challenge is over, the result to share.
+fn copy(state: &GameState) -> Result<EventResponse, Error> {
+    if state.phase == GamePhase::Finished
+        && let Some(challenge) = &state.challenge
+    {
+        execute!(
+            stdout(),
+            CopyToClipboard::to_clipboard_from(
+                challenge.share_string(state.score, state.elapsed())
+            )
+        )
+        .map_err(|_| Error::ClipboardUnavailable)?;
+        return Ok(EventResponse::ChangeStatus(
+            "Copied your result to your clipboard.".set_style(Style::new().fg(Color::Green)),
+        ));
+    }
+    execute!(
+        stdout(),
+        CopyToClipboard::to_clipboard_from(state.seed.to_string())
+    )
+    .map_err(|_| Error::ClipboardUnavailable)?;
+    Ok(EventResponse::ChangeStatus(
+        "Copied the seed to your clipboard.".set_style(Style::new().fg(Color::Green)),
+    ))
+}
+
+/// Shows the hand's letters in a random order, for a fresh look at them.
+///
+/// This doesn't touch the game's own random draws, so replays aren't thrown off.
+fn shuffle_hand(state: &mut GameState) -> EventResponse {
+    let mut order: Vec<char> = count_tiles(state.hand.tiles())
+        .into_iter()
+        .map(|(tile, ..)| tile)
+        .collect();
+    order.shuffle(&mut rand::rng());
+    state.hand_order = order;
+    EventResponse::ChangeStatus("Shuffled the hand.".set_style(Style::new().fg(Color::Cyan)))
+}
+
+/// Sorts the hand with its vowels first, or if they already are, its consonants first.
+fn sort_hand(state: &mut GameState) -> EventResponse {
+    let vowels_first: bool = !state
+        .hand_order
+        .first()
+        .is_some_and(|tile: &char| is_vowel(*tile));
+    let mut order: Vec<char> = count_tiles(state.hand.tiles())
+        .into_iter()
+        .map(|(tile, ..)| tile)
+        .collect();
+    order.sort_by_key(|tile: &char| is_vowel(*tile) != vowels_first);
+    state.hand_order = order;
+    EventResponse::ChangeStatus(
+        if vowels_first {
+            "Sorted the hand, vowels first."
+        } else {
+            "Sorted the hand, consonants first."
+        }
+        .set_style(Style::new().fg(Color::Cyan)),
+    )
+}
+
+/// Exports the game so far, with when each move was made.
+fn export(state: &GameState) -> Result<EventResponse, Error> {
+    let path: PathBuf = notation::export(
+        state.seed,
+        &state.rules,
+        &state.actions,
+        &state.action_times,
+        state.hints,
+        state.camera.stats.checksum,
+    )?;
+    Ok(EventResponse::ChangeStatus(
+        format!("Exported the game to {}.", path.display())
+            .set_style(Style::new().fg(Color::Green)),
+    ))
+}
+
+/// Picking tiles up from the hand and putting them on the board with the mouse,
+/// by clicking one then the other or by dragging.
+fn mouse_controls(state: &mut GameState, event: MouseEvent) -> Result<EventResponse, Error> {
+    state.last_input = Instant::now();
+    if state.phase != GamePhase::Playing || state.paused_since.is_some() {
+        state.camera.hover = None;
+        return Ok(EventResponse::Pass);
+    }
+    let cell: Option<Coordinate> = state.camera.cell_at(event.column, event.row);
+    match event.kind {
+        MouseEventKind::Moved | MouseEventKind::Drag(MouseButton::Left) => {
+            state.camera.hover = cell;
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            let spot: Position = Position::new(event.column, event.row);
+            if let Some(&(_, tile)) = state.hand_spots.iter().find(|(x, _)| x.contains(spot)) {
+                if tile == BLANK {
                     return Ok(EventResponse::ChangeStatus(
-                        "Guavagrams!".set_style(Style::new().fg(Color::Green)),
+                        "A blank goes down by typing the letter it stands for."
+                            .set_style(Style::new().fg(Color::Yellow)),
                     ));
                 }
-                state
-                    .tileset
-                    .1
-                    .append(&mut Distribution::pull_from_pile(&mut state.tileset.0, 1)?);
+                state.camera.held = Some(tile);
                 return Ok(EventResponse::ChangeStatus(
-                    "Peel!".set_style(Style::new().fg(Color::Green)),
+                    format!("Holding '{tile}'. Click a cell, or let go over one, to put it down.")
+                        .set_style(Style::new().fg(Color::Cyan)),
                 ));
             }
-            KeyCode::Char(letter)
-                if event.modifiers.contains(KeyModifiers::CONTROL)
-                    && state.tileset.1.contains(&letter)
-                    && state.game_end.is_none() =>
-            {
-                if state.tileset.0.len() >= 3 {
-                    state
-                        .tileset
-                        .1
-                        .append(&mut Distribution::pull_from_pile(&mut state.tileset.0, 3)?);
-                    state.tileset.0.push(
-                        state.tileset.1.remove(
-                            state
-                                .tileset
-                                .1
-                                .iter()
-                                .position(|x: &char| *x == letter)
-                                .ok_or(Error::NoMoreTiles)?,
-                        ),
-                    );
-
-                    state.score -= state.score / 20;
-                    state.tileset.0.shuffle(&mut ThreadRng::default());
-                }
+            match (state.camera.held, cell) {
+                (Some(tile), Some(cell)) => return put_down(state, tile, cell),
+                (None, Some(cell)) => state.camera.cursor = cell,
+                _ => (),
+            }
+        }
+        // Letting go over the board finishes a drag from the hand.
+        MouseEventKind::Up(MouseButton::Left) => {
+            if let (Some(tile), Some(cell)) = (state.camera.held, cell) {
+                return put_down(state, tile, cell);
+            }
+        }
+        MouseEventKind::Down(MouseButton::Right) => state.camera.held = None,
+        _ => (),
+    }
+    Ok(EventResponse::Pass)
+}
+
+/// Puts a tile held with the mouse down on a cell. It stays held if the cell's taken.
+fn put_down(state: &mut GameState, tile: char, cell: Coordinate) -> Result<EventResponse, Error> {
+    let moves: usize = state.actions.len();
+    state.camera.cursor = cell;
+    let response: EventResponse = action::apply(state, Action::Place(tile, cell))?;
+    if state.actions.len() != moves {
+        state.camera.held = None;
+    }
+    after_move(state, moves, response)
+}
+
+/// The controls for the pause screen.
+///
+/// Besides picking from the menu, any other key resumes the game, and does nothing else.
+fn pause_controls(state: &mut GameState, code: KeyCode) -> EventResponse {
+    match (code, state.pause_choice) {
+        (KeyCode::Up | KeyCode::Down, choice) => {
+            state.pause_choice = match choice {
+                PauseChoice::Resume => PauseChoice::Quit,
+                PauseChoice::Quit => PauseChoice::Resume,
+            };
+        }
+        (KeyCode::Esc | KeyCode::Char('Q'), _) | (KeyCode::Enter, PauseChoice::Quit) => {
+            return EventResponse::Quit;
+        }
+        _ => state.resume(),
+    }
+    EventResponse::Pass
+}
 
+/// The controls for playing the game.
+fn playing_controls(state: &mut GameState, event: KeyEvent) -> Result<EventResponse, Error> {
+    let moves: usize = state.actions.len();
+    // Adding a house word has to be asked for twice in a row.
+    let confirmed: bool = std::mem::take(&mut state.confirming_house_word);
+    let response: EventResponse = match event.code {
+        _ if state.keymap.tapped().is_some() => double_tap(state)?,
+        KeyCode::Char('G') => action::apply(state, Action::Peel)?,
+        KeyCode::Char('P') => {
+            state.pause();
+            EventResponse::Pass
+        }
+        KeyCode::Char('H') => add_house_word(state, confirmed)?,
+        KeyCode::Char('M') => select(state)?,
+        KeyCode::Char('?') => action::apply(state, Action::Hint)?,
+        KeyCode::Char('U') => history::undo(state),
+        KeyCode::Char('R') => history::redo(state)?,
+        KeyCode::Char('S') => {
+            let slot: String = state
+                .save_slot
+                .clone()
+                .unwrap_or_else(|| state.seed.to_string());
+            save::save(state, &save::slot_path(Path::new(SAVE_DIRECTORY), &slot))?;
+            EventResponse::ChangeStatus(
+                format!("Saved the game to \"{slot}\". Start with --resume to carry on.")
+                    .set_style(Style::new().fg(Color::Green)),
+            )
+        }
+        KeyCode::Char(letter)
+            if event.modifiers.contains(KeyModifiers::ALT)
+                && state.distribution.contains_letter(letter) =>
+        {
+            state.watched_letter = Some(letter.to_lowercase().next().unwrap_or(letter));
+            EventResponse::Pass
+        }
+        KeyCode::Char(letter)
+            if event.modifiers.contains(KeyModifiers::CONTROL)
+                && state.distribution.contains_tile(letter) =>
+        {
+            action::apply(state, Action::Dump(letter))?
+        }
+        KeyCode::Char(letter) if state.distribution.contains_letter(letter) => {
+            action::apply(state, Action::Place(letter, state.camera.cursor))?
+        }
+        KeyCode::Backspace => action::apply(state, Action::PickUp(state.camera.cursor))?,
+        KeyCode::Right | KeyCode::Left | KeyCode::Up | KeyCode::Down => {
+            let direction: Coordinate = match event.code {
+                KeyCode::Right => Coordinate(1, 0),
+                KeyCode::Left => Coordinate(-1, 0),
+                KeyCode::Up => Coordinate(0, 1),
+                _ => Coordinate(0, -1),
+            };
+            action::apply(state, Action::PickUpRun(state.camera.cursor, direction))?
+        }
+        _ => EventResponse::Pass,
+    };
+    after_move(state, moves, response)
+}
+
+/// Follows up on a move: points out a stuck hand or a sprawling board, starts an
+/// auto-peel, and keeps a finished game.
+///
+/// `moves` is how many moves had been made before.
+fn after_move(
+    state: &mut GameState,
+    moves: usize,
+    mut response: EventResponse,
+) -> Result<EventResponse, Error> {
+    if state.actions.len() != moves {
+        state.weigh_hand();
+    }
+
+    // Rather than leave the player hunting for a move that might not be there, say so. The
+    // solver doesn't try everything, so this can't promise there's nothing to play.
+    if state.phase == GamePhase::Playing
+        && state.actions.len() != moves
+        && let Some(stuck) = Stuck::check(
+            state.hand.tiles(),
+            &state.camera.grid,
+            &state.dictionary,
+            &state.scoring,
+            &state.rules,
+        )
+    {
+        response = EventResponse::ChangeStatus(
+            format!(
+                "No simple placement found for your hand. If you're stuck, try trading in '{}' (Ctrl + {}).",
+                stuck.dump, stuck.dump
+            )
+            .set_style(Style::new().fg(Color::Yellow)),
+        );
+    }
+
+    // A peel is a good time to point out a board that's spreading further than it needs to.
+    if state.phase == GamePhase::Playing
+        && state.actions.len() != moves
+        && state.actions.last() == Some(&Action::Peel)
+        && let Some(sprawl) = Sprawl::check(&state.camera.stats)
+    {
+        response = EventResponse::ChangeStatus(
+            format!(
+                "Peel! The board is {}x{}, but could fit in about {}x{}. Tighter boards leave more room to play.",
+                sprawl.dimensions.0, sprawl.dimensions.1, sprawl.tighter, sprawl.tighter
+            )
+            .set_style(Style::new().fg(Color::Yellow)),
+        );
+    }
+
+    // With auto-peel on, placing the last tile starts the countdown to a peel.
+    if state.phase == GamePhase::Playing
+        && state.hand.is_empty()
+        && matches!(state.actions.last(), Some(Action::Place(..)))
+        && state.actions.len() != moves
+        && let Some(grace) = state.auto_peel
+    {
+        state.pending_peel = Some(Instant::now() + grace);
+        response = EventResponse::ChangeStatus(
+            format!(
+                "Peeling in {:.1}s. Press any key to call it off.",
+                grace.as_secs_f64()
+            )
+            .set_style(Style::new().fg(Color::Cyan)),
+        );
+    }
+
+    // Keep every finished game around, however it finished.
+    if state.phase == GamePhase::Finished {
+        state.keep_finished_game()?;
+    }
+    Ok(response)
+}
+
+/// Starts picking out tiles, lifts the ones picked out, or puts lifted ones down at the cursor.
+fn select(state: &mut GameState) -> Result<EventResponse, Error> {
+    let cursor: Coordinate = state.camera.cursor;
+    let status = |text: &str| {
+        EventResponse::ChangeStatus(text.to_string().set_style(Style::new().fg(Color::Cyan)))
+    };
+    match state.camera.selection {
+        None => {
+            state.camera.selection = Some(Selection::Marking(cursor));
+            Ok(status(
+                "Move to the opposite corner, then press Shift + M to lift the tiles. Esc lets go.",
+            ))
+        }
+        Some(Selection::Marking(corner)) => {
+            let (min, max): (Coordinate, Coordinate) = camera::region(corner, cursor);
+            if !state
+                .camera
+                .grid
+                .occupied_cells()
+                .any(|(x, _)| (min.0..=max.0).contains(&x.0) && (min.1..=max.1).contains(&x.1))
+            {
+                state.camera.selection = None;
                 return Ok(EventResponse::ChangeStatus(
-                    "Deducted 5% of points for trading in tiles."
-                        .set_style(Style::new().fg(Color::Red)),
+                    "There aren't any tiles there to move."
+                        .set_style(Style::new().fg(Color::Yellow)),
                 ));
             }
-            KeyCode::Char(letter)
-                if (letter.is_lowercase() || !letter.is_alphabetic())
-                    && state.distribution.contains_letter(letter)
-                    && state.tileset.1.contains(&letter)
-                    && state.game_end.is_none() =>
-            {
-                // Check if a tile was actually put down before removing it from our hand.
-                if state.camera.put(letter) {
-                    state.tileset.1.remove(
-                        state
-                            .tileset
-                            .1
-                            .iter()
-                            .position(|x: &char| *x == letter)
-                            .unwrap(),
-                    );
-                }
-            }
-            KeyCode::Backspace if state.game_end.is_none() => {
-                if let Some(tile) = state.camera.pick_up() {
-                    state.tileset.1.push(tile);
+            state.camera.selection = Some(Selection::Lifted {
+                region: (min, max),
+                from: cursor,
+            });
+            Ok(status(
+                "Move to where the tiles should go, then press Shift + M to put them down.",
+            ))
+        }
+        Some(Selection::Lifted { region, from }) => {
+            // A move that doesn't fit leaves the tiles lifted, to try somewhere else.
+            let response: EventResponse =
+                action::apply(state, Action::Move(region.0, region.1, cursor - from))?;
+            state.camera.selection = None;
+            Ok(response)
+        }
+    }
+}
+
+/// Adds the last word a check turned down to the house dictionary, once the player's sure.
+fn add_house_word(state: &mut GameState, confirmed: bool) -> Result<EventResponse, Error> {
+    let Some(word) = state.rejected_word.clone() else {
+        return Ok(EventResponse::ChangeStatus(
+            "No word has been turned down yet.".set_style(Style::new().fg(Color::Yellow)),
+        ));
+    };
+    if !confirmed {
+        state.confirming_house_word = true;
+        return Ok(EventResponse::ChangeStatus(
+            format!("Add \"{word}\" to your house dictionary? Press Shift + H again to confirm.")
+                .set_style(Style::new().fg(Color::Cyan)),
+        ));
+    }
+
+    dictionary::add_house_word(Path::new(HOUSE_PATH), &word)
+        .map_err(|exception| Error::HouseDictionaryFailed(exception.to_string()))?;
+    state.dictionary.extend([word.clone()]);
+    state.rejected_word = None;
+    state.camera.flagged.clear();
+    state.camera.check_health(&state.dictionary, &state.rules);
+    Ok(EventResponse::ChangeStatus(
+        format!("Added \"{word}\" to your house dictionary.")
+            .set_style(Style::new().fg(Color::Green)),
+    ))
+}
+
+/// Does what the key just double tapped is bound to, instead of what it does on its own.
+fn double_tap(state: &mut GameState) -> Result<EventResponse, Error> {
+    match state.keymap.tapped() {
+        Some(DoubleTap::ClearWord) => match state.camera.word_under_cursor() {
+            Some((start, direction)) => action::apply(state, Action::PickUpRun(start, direction)),
+            None => Ok(EventResponse::Pass),
+        },
+        Some(DoubleTap::Check) => Ok(EventResponse::ChangeStatus(
+            match action::validate_board(state) {
+                Ok(()) => "The board would pass a peel.".set_style(Style::new().fg(Color::Green)),
+                Err(exception) => {
+                    action::blame(state, &exception);
+                    format!("The board wouldn't pass a peel. {exception}")
+                        .set_style(Style::new().fg(Color::Yellow))
                 }
-            }
-            _ => (),
+            },
+        )),
+        None => Ok(EventResponse::Pass),
+    }
+}
+
+/// The controls for stepping through an imported game.
+fn reviewing_controls(state: &mut GameState, code: KeyCode) -> Result<EventResponse, Error> {
+    let now: Instant = Instant::now();
+    match code {
+        KeyCode::Char('N') => return replay::step(state),
+        KeyCode::Char('A') => return replay::step_all(state),
+        KeyCode::Char(' ') => state.autoplay.toggle(now),
+        KeyCode::Char('+' | '=') => state.autoplay.faster(now),
+        KeyCode::Char('-') => state.autoplay.slower(now),
+        _ => return Ok(EventResponse::Pass),
+    }
+    Ok(EventResponse::ChangeStatus(
+        if state.autoplay.is_playing() {
+            format!("Playing back at {}x.", state.autoplay.speed())
+        } else {
+            format!(
+                "Stopped the replay. It plays at {}x.",
+                state.autoplay.speed()
+            )
         }
+        .set_style(Style::new().fg(Color::Cyan)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use std::time::Instant;
+
+    use ratatui::{style::Modifier, text::Line};
+
+    use super::{event_handler, score_line};
+    use crate::{
+        EventResponse, GameState,
+        config::Config,
+        dictionary::{Dictionary, Normalization},
+        grid::Coordinate,
+        tiles::Hand,
+    };
+
+    #[test]
+    fn test_double_tap_check_doesnt_peel() {
+        let dictionary: Dictionary =
+            Dictionary::new(["cat"].map(String::from), Normalization::default());
+        let mut state: GameState = GameState::new(dictionary, &Config::default(), 3);
+        state.start();
+        // A valid board and an empty hand, so a lone Shift + G would peel.
+        for (x, letter) in (0..).zip("cat".chars()) {
+            state.camera.put(Coordinate(x, 0), letter);
+        }
+        state.hand = Hand::default();
+
+        let g: Event = Event::Key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT));
+        event_handler(&mut state, &g).unwrap();
+        let response: EventResponse = event_handler(&mut state, &g).unwrap();
+        assert!(state.actions.is_empty());
+        assert!(
+            matches!(response, EventResponse::ChangeStatus(status) if status.content.contains("would pass a peel"))
+        );
     }
 
-    Ok(EventResponse::Pass)
+    #[test]
+    fn test_reduced_motion_score_line() {
+        let mut state: GameState = GameState::new(Dictionary::default(), &Config::default(), 3);
+        state.last_delta = Some((5, Instant::now()));
+        let line: Line = score_line(&state);
+        assert!(line.spans[1].style.add_modifier.contains(Modifier::BOLD));
+
+        state.reduced_motion = true;
+        let line: Line = score_line(&state);
+        assert_eq!(line.spans[1].content, "  +5");
+        assert!(line.spans[1].style.add_modifier.is_empty());
+    }
+
+    #[test]
+    fn test_stuck_hand_with_an_extension() {
+        let dictionary: Dictionary =
+            Dictionary::new(["cat", "cats"].map(String::from), Normalization::default());
+        let mut state: GameState = GameState::new(dictionary, &Config::default(), 3);
+        state.start();
+        for (x, letter) in (0..).zip("ca".chars()) {
+            state.camera.put(Coordinate(x, 0), letter);
+        }
+        state.hand = Hand::default();
+        state.hand.add('t');
+        state.hand.add('s');
+        state.camera.cursor = Coordinate(2, 0);
+
+        // Only "cats" is left to make, which the solver can't see.
+        let t: Event = Event::Key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        let response: EventResponse = event_handler(&mut state, &t).unwrap();
+        assert_eq!(state.hand.tiles(), ['s']);
+        assert!(
+            matches!(response, EventResponse::ChangeStatus(status) if status.content.starts_with("No simple placement found"))
+        );
+    }
 }
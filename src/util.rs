@@ -1,37 +1,22 @@
-use std::{collections::HashMap, fmt::Write, time::Duration};
+use std::{collections::HashMap, time::Duration};
 
 use rand::distr::weighted::WeightedIndex;
+use serde::Deserialize;
 
 use crate::dictionary::LetterDistribution;
 
-/// A macro similar to `vec![$elem; $size]` which returns a boxed array.
-///
-/// ```rustc
-///     let _: Box<[u8; 1024]> = box_array![0; 1024];
-/// ```
-// Stolen with <3 from https://stackoverflow.com/questions/25805174/creating-a-fixed-size-array-on-heap-in-rust
-#[macro_export]
-macro_rules! box_array {
-    ($val:expr ; $len:expr) => {{
-        // Use a generic function so that the pointer cast remains type-safe
-        fn vec_to_boxed_array<T>(vec: Vec<T>) -> Box<[T; $len]> {
-            let boxed_slice = vec.into_boxed_slice();
-
-            let ptr = ::std::boxed::Box::into_raw(boxed_slice).cast::<[T; $len]>();
-
-            unsafe { Box::from_raw(ptr) }
-        }
-
-        vec_to_boxed_array(vec![$val; $len])
-    }};
-}
-
 /// Creates a weighted index from a list of entries and weights.
+///
+/// # Panics
+///
+/// If the list is empty, or every weight is zero.
+#[must_use]
 pub fn create_weights(list: &LetterDistribution) -> WeightedIndex<usize> {
     WeightedIndex::new(list.iter().map(|item: &(char, usize)| item.1)).unwrap()
 }
 
 /// Creates an HH:mm:ss timestamp from a duration.
+#[must_use]
 pub fn format_duration(duration: Duration) -> String {
     let secs: u64 = duration.as_secs();
     let hours: u64 = secs / 3600;
@@ -41,27 +26,122 @@ pub fn format_duration(duration: Duration) -> String {
     format!("{hours:02}:{minutes:02}:{seconds:02}")
 }
 
-/// Makes a string of each letter in "'x' (y)" format.
-pub fn format_tile_list(hand: &[char]) -> String {
-    let mut output: String = String::new();
+/// How a clock is written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerFormat {
+    /// Hours, minutes and seconds, like `00:04:05`.
+    Full,
+    /// Minutes and seconds under an hour, like `04:05`.
+    #[default]
+    Compact,
+    /// Minutes, seconds and tenths under an hour, like `04:05.3`.
+    Tenths,
+}
 
-    // Create a map of every tile and how many of it we have.
-    let mut count = HashMap::new();
-    for tile in hand {
-        if let Some(entry) = count.get_mut(&tile) {
-            *entry += 1;
-        } else {
-            count.insert(tile, 1);
+/// Writes a duration out the way a timer format says. Past an hour, every format
+/// writes the hours out like `Full`.
+#[must_use]
+pub fn format_timer(duration: Duration, format: TimerFormat) -> String {
+    let secs: u64 = duration.as_secs();
+    if format == TimerFormat::Full || secs >= 3600 {
+        return format_duration(duration);
+    }
+    let minutes: u64 = secs / 60;
+    let seconds: u64 = secs % 60;
+    match format {
+        TimerFormat::Tenths => {
+            let tenths: u32 = duration.subsec_millis() / 100;
+            format!("{minutes:02}:{seconds:02}.{tenths}")
         }
+        _ => format!("{minutes:02}:{seconds:02}"),
     }
+}
+
+/// Scrambles a number so that close inputs give unrelated outputs (`SplitMix64`).
+#[must_use]
+pub const fn mix(value: u64) -> u64 {
+    let mut z: u64 = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Vowels, accented ones included, so non-English dictionaries get their hands balanced too.
+const VOWELS: &str = "aeiouáéíóúàèìòùâêîôûäëïöüåæøœãõ";
 
-    let mut count = count.iter().collect::<Vec<_>>();
+/// Checks if a tile is a vowel.
+#[must_use]
+pub fn is_vowel(tile: char) -> bool {
+    tile.to_lowercase().all(|x: char| VOWELS.contains(x))
+}
+
+/// Calculates the chance that the next tile pulled from a pile matches the predicate.
+#[allow(clippy::cast_precision_loss)]
+pub fn draw_probability(pile: &[char], predicate: impl Fn(char) -> bool) -> f64 {
+    if pile.is_empty() {
+        return 0.0;
+    }
+    pile.iter().filter(|tile: &&char| predicate(**tile)).count() as f64 / pile.len() as f64
+}
+
+/// Counts how many of each tile there are, in alphabetical order.
+#[must_use]
+pub fn count_tiles(tiles: &[char]) -> Vec<(char, usize)> {
+    // Create a map of every tile and how many of it we have.
+    let mut count: HashMap<char, usize> = HashMap::new();
+    for tile in tiles {
+        *count.entry(*tile).or_default() += 1;
+    }
+
+    let mut count = count.into_iter().collect::<Vec<_>>();
     // Sort as to not have a different order every frame.
-    count.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+    count.sort_by_key(|(entry, ..)| *entry);
+    count
+}
+
+/// Counts how many of each tile there are, in the order the letters come in `order`.
+///
+/// Letters that aren't in it go after the ones that are, alphabetically, so an empty
+/// order is the same as `count_tiles`.
+#[must_use]
+pub fn count_tiles_in(tiles: &[char], order: &[char]) -> Vec<(char, usize)> {
+    let mut count: Vec<(char, usize)> = count_tiles(tiles);
+    count.sort_by_key(|(tile, ..)| {
+        order
+            .iter()
+            .position(|x: &char| x == tile)
+            .unwrap_or(order.len())
+    });
+    count
+}
 
-    for (entry, amount) in count {
-        write!(&mut output, "'{entry}' ({amount}), ").unwrap();
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{TimerFormat, count_tiles_in, format_timer};
+
+    #[test]
+    fn test_count_tiles_in() {
+        let tiles: Vec<char> = "banana".chars().collect();
+        assert_eq!(count_tiles_in(&tiles, &[]), [('a', 3), ('b', 1), ('n', 2)]);
+        // Letters left out of the order go at the end, alphabetically.
+        assert_eq!(
+            count_tiles_in(&tiles, &['n', 'x']),
+            [('n', 2), ('a', 3), ('b', 1)]
+        );
     }
 
-    output
+    #[test]
+    fn test_format_timer() {
+        let time: Duration = Duration::from_millis(245_380);
+        assert_eq!(format_timer(time, TimerFormat::Full), "00:04:05");
+        assert_eq!(format_timer(time, TimerFormat::Compact), "04:05");
+        assert_eq!(format_timer(time, TimerFormat::Tenths), "04:05.3");
+        // Past an hour, the hours are written out whatever the format.
+        let long: Duration = Duration::from_secs(3725);
+        assert_eq!(format_timer(long, TimerFormat::Compact), "01:02:05");
+        assert_eq!(format_timer(long, TimerFormat::Tenths), "01:02:05");
+    }
 }
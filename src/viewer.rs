@@ -0,0 +1,152 @@
+//! A read-only look at a board from a save or an exported game.
+//!
+//! Nothing here needs a dictionary or a hand, so shared boards and puzzles can be
+//! looked over without dealing a game.
+
+use std::{fs, io, path::Path};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    DefaultTerminal, Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Styled},
+    text::{Line, Span},
+};
+
+use crate::{
+    Error,
+    camera::Camera,
+    grid::{Coordinate, Grid, Word},
+    notation,
+    save::Save,
+    screenshot,
+};
+
+/// Reads the board out of a save, or an exported game.
+pub fn load_board(path: &Path) -> Result<Grid<Option<char>>, Error> {
+    let contents: String =
+        fs::read_to_string(path).map_err(|exception| Error::ImportFailed(exception.to_string()))?;
+    if let Ok(save) = serde_json::from_str::<Save>(&contents) {
+        return Ok(save.into_board());
+    }
+    Ok(notation::from_notation(&contents)?.board())
+}
+
+/// What the viewer is showing.
+struct Viewer {
+    camera: Camera,
+    /// The search being typed, if one is.
+    typing: Option<String>,
+    /// The last search made.
+    query: String,
+    /// How many matches of the last search have been jumped to.
+    jumps: usize,
+    status: Span<'static>,
+}
+
+impl Viewer {
+    /// Moves the cursor to the next place the search shows up, going round the board's words in order.
+    fn jump(&mut self) {
+        if self.query.is_empty() {
+            return;
+        }
+        let matches: Vec<Coordinate> = self
+            .camera
+            .stats
+            .words
+            .iter()
+            .filter_map(|word: &Word| word.find(&self.query))
+            .collect();
+        self.status = if matches.is_empty() {
+            format!("\"{}\" isn't on the board.", self.query).set_style(Style::new().fg(Color::Red))
+        } else {
+            let index: usize = self.jumps % matches.len();
+            self.camera.cursor = matches[index];
+            self.jumps += 1;
+            format!(
+                "\"{}\", match {} of {}.",
+                self.query,
+                index + 1,
+                matches.len()
+            )
+            .into()
+        };
+    }
+}
+
+/// Shows a board with nothing but moving around, searching and screenshots, until the player quits.
+pub fn run(terminal: &mut DefaultTerminal, grid: Grid<Option<char>>) -> io::Result<()> {
+    let mut viewer: Viewer = Viewer {
+        camera: Camera::new(grid),
+        typing: None,
+        query: String::new(),
+        jumps: 0,
+        status: Span::default(),
+    };
+
+    loop {
+        let mut screenshot_requested: bool = false;
+        let frame = terminal.draw(|frame: &mut Frame| draw(frame, &mut viewer))?;
+        let Event::Key(event) = event::read()? else {
+            continue;
+        };
+        if event.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if let Some(typing) = &mut viewer.typing {
+            match event.code {
+                KeyCode::Char(letter) => typing.push(letter),
+                KeyCode::Backspace => {
+                    typing.pop();
+                }
+                KeyCode::Enter => {
+                    viewer.query = typing.to_lowercase();
+                    viewer.typing = None;
+                    viewer.jumps = 0;
+                    viewer.jump();
+                }
+                KeyCode::Esc => viewer.typing = None,
+                _ => (),
+            }
+            continue;
+        }
+        viewer.status = Span::default();
+        match event.code {
+            KeyCode::Right => viewer.camera += Coordinate(1, 0),
+            KeyCode::Left => viewer.camera += Coordinate(-1, 0),
+            KeyCode::Up => viewer.camera += Coordinate(0, 1),
+            KeyCode::Down => viewer.camera += Coordinate(0, -1),
+            KeyCode::Char('/') => viewer.typing = Some(String::new()),
+            KeyCode::Char('N') => viewer.jump(),
+            KeyCode::Char('F') => screenshot_requested = true,
+            KeyCode::Esc | KeyCode::Char('Q') => return Ok(()),
+            _ => (),
+        }
+
+        // The screenshot is of the frame that was on screen when it was asked for.
+        if screenshot_requested {
+            viewer.status = match screenshot::save(frame.buffer) {
+                Ok(path) => format!("Saved a screenshot to {}.ans/.html.", path.display())
+                    .set_style(Style::new().fg(Color::Green)),
+                Err(exception) => exception.to_string().set_style(Style::new().fg(Color::Red)),
+            };
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, viewer: &mut Viewer) {
+    let [board, footer]: [Rect; 2] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+    frame.render_widget(&mut viewer.camera, board);
+
+    let line: Line = match &viewer.typing {
+        Some(typing) => Line::raw(format!("Search: {typing}_")),
+        None if !viewer.status.content.is_empty() => Line::from(viewer.status.clone()),
+        None => Line::styled(
+            " ↑/↓/←/→ Move  / Search  Shift + N Next Match  Shift + F Screenshot  Shift + Q/Esc Quit",
+            Style::new().fg(Color::DarkGray),
+        ),
+    };
+    frame.render_widget(line, footer);
+}